@@ -0,0 +1,73 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use domain_changer::types::{Config, Options};
+use domain_changer::{parse_string_with, parse_strings};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use std::hint::black_box;
+
+/// Archived messages, a third of them contain links of the default config
+fn messages(len: usize) -> Vec<String> {
+    (0..len)
+        .map(|index| match index % 3 {
+            0 => format!("Check this https://twitter.com/user/status/{} out", index),
+            1 => format!("watch youtu.be/{} and reddit.com/r/rust/{}", index, index),
+            _ => format!("Just a message without links, number {}", index),
+        })
+        .collect()
+}
+
+fn batch(c: &mut Criterion) {
+    let (config, options) = (Config::default(), Options::default());
+    let messages: Vec<String> = messages(10_000);
+    let mut group = c.benchmark_group("batch");
+    group.throughput(Throughput::Elements(messages.len() as u64));
+
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            messages
+                .iter()
+                .map(|message| parse_string_with(&config, black_box(message.clone()), &options))
+                .collect::<Vec<String>>()
+        })
+    });
+    for threads in [1, 2, 4, 8] {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("parse_strings", threads),
+            &messages,
+            |b, messages| {
+                b.iter(|| {
+                    // The messages are cloned like the sequential baseline
+                    pool.install(|| {
+                        parse_strings(
+                            &config,
+                            black_box(messages).par_iter().map(String::clone),
+                            &options,
+                        )
+                    })
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, batch);
+criterion_main!(benches);
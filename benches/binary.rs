@@ -0,0 +1,57 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use domain_changer::types::{Config, Domain, ToFromJson};
+use std::hint::black_box;
+
+/// Config with `len` domains, every domain has `frontends` targets like the configs
+/// that are compiled from services, see `Service`
+fn config(len: usize, frontends: usize) -> Config {
+    Config::new(
+        (0..len)
+            .map(|index| {
+                Domain::try_from((
+                    format!("https://source-{}.example.com/", index / frontends).as_str(),
+                    format!("https://frontend-{}.example.org/", index % frontends).as_str(),
+                ))
+                .unwrap()
+            })
+            .collect(),
+    )
+}
+
+/// Compare loading the same config from json and from binary blob
+fn load(c: &mut Criterion) {
+    for (name, frontends) in [("load", 1), ("load_services", 4)] {
+        let mut group = c.benchmark_group(name);
+        for len in [100, 1_000, 10_000] {
+            let config: Config = config(len, frontends);
+            let json: String = config.to_json().unwrap();
+            let bytes: Vec<u8> = config.to_bytes();
+
+            group.bench_with_input(BenchmarkId::new("from_json", len), &json, |b, json| {
+                b.iter(|| Config::from_json(black_box(json)).unwrap())
+            });
+            group.bench_with_input(BenchmarkId::new("from_bytes", len), &bytes, |b, bytes| {
+                b.iter(|| Config::from_bytes(black_box(bytes)).unwrap())
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, load);
+criterion_main!(benches);
@@ -0,0 +1,109 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Asserts the guarantees of the limits with arbitrary limits:
+//! - the input over `max_input_bytes` is rejected with `LimitAction::Error`
+//! - the text that is not parsed is kept byte-for-byte with `LimitAction::Truncate`
+//! - the work is bounded by the length of the input, no quadratic pass over a word
+
+#![no_main]
+
+use domain_changer::parse_string_report;
+use domain_changer::types::{Config, Limit, LimitAction, Limits, Options, WarningKind};
+use libfuzzer_sys::fuzz_target;
+use std::time::{Duration, Instant};
+
+/// The time bound of parsing, it is linear in the length of the input and far
+/// above the parsing time, a quadratic pass over a long word exceeds it
+fn time_bound(len: usize) -> Duration {
+    Duration::from_millis(50) + Duration::from_micros(2) * u32::try_from(len).unwrap_or(u32::MAX)
+}
+
+fuzz_target!(|input: (String, [u8; 4], bool)| {
+    let (text, [bytes, candidates, length, rules], error) = input;
+    let limits: Limits = Limits {
+        max_input_bytes: Some(usize::from(bytes)),
+        max_candidates: Some(usize::from(candidates % 16)),
+        max_url_length: Some(usize::from(length)),
+        max_rules: Some(usize::from(rules)),
+        exceeded: if error {
+            LimitAction::Error
+        } else {
+            LimitAction::Truncate
+        },
+    };
+    let options: Options = Options {
+        limits: limits.clone(),
+        ..Options::default()
+    };
+    let started: Instant = Instant::now();
+    let result = parse_string_report(&Config::default(), text.clone(), &options);
+    assert!(
+        started.elapsed() < time_bound(text.len()),
+        "parsing {} bytes took {:?}",
+        text.len(),
+        started.elapsed()
+    );
+    // The untrusted preset bounds the work of any input
+    let started: Instant = Instant::now();
+    let _ = parse_string_report(
+        &Config::default(),
+        text.clone(),
+        &Options {
+            limits: Limits::untrusted(),
+            ..Options::default()
+        },
+    );
+    assert!(started.elapsed() < time_bound(text.len()));
+
+    if error {
+        if text.len() > usize::from(bytes) {
+            assert!(result.unwrap_err().is_limit_exceeded());
+        }
+        return;
+    }
+    let report = result.expect("the truncate action never fails");
+    // The rest of the text is not parsed, starting from the earliest truncation
+    let Some(start) = report
+        .warnings
+        .iter()
+        .filter(|warning| {
+            matches!(
+                warning.kind,
+                WarningKind::LimitExceeded(Limit::InputBytes | Limit::Candidates | Limit::Rules)
+            )
+        })
+        .inspect(|warning| assert_eq!(warning.span.end, text.len()))
+        .map(|warning| warning.span.start)
+        .min()
+    else {
+        return;
+    };
+    // The parsed part is the same as parsing it alone, and the rest is byte-for-byte
+    // identical to the input, so no word that crosses the truncation is rewritten
+    let parsed = parse_string_report(
+        &Config::default(),
+        text[..start].to_owned(),
+        &Options {
+            limits: Limits {
+                max_url_length: limits.max_url_length,
+                ..Limits::default()
+            },
+            ..Options::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(report.text, format!("{}{}", parsed.text, &text[start..]));
+});
@@ -0,0 +1,37 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parsing an arbitrary text with the untrusted limits does not panic and
+//! does not exceed the limits
+
+#![no_main]
+
+use domain_changer::parse_string_report;
+use domain_changer::types::{Config, Limits, Options};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|text: String| {
+    let options: Options = Options {
+        limits: Limits::untrusted(),
+        ..Options::default()
+    };
+    let report = parse_string_report(&Config::default(), text.clone(), &options)
+        .expect("the truncate action never fails");
+    for warning in report.warnings {
+        assert!(warning.span.end <= text.len());
+        assert!(text.is_char_boundary(warning.span.start));
+        assert!(text.is_char_boundary(warning.span.end));
+    }
+});
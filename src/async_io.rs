@@ -0,0 +1,288 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! [`AsyncRead`] and [`AsyncWrite`] adapters of [`StreamRewriter`] for [tokio](https://docs.rs/tokio)
+
+use crate::types::{Config, Options};
+use crate::StreamRewriter;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// The size of the chunks that are read from the inner reader
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// [`AsyncRead`] adapter that rewrites the bytes of the inner reader, see [`StreamRewriter`]
+///
+/// # Example
+/// ```rust
+/// use domain_changer::types::{Config, Options};
+/// use domain_changer::RewriteReader;
+/// use std::sync::Arc;
+/// use tokio::io::AsyncReadExt;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let body: &[u8] = b"Follow me: twitter.com/Awiteb";
+/// let mut reader = RewriteReader::new(body, Arc::new(Config::default()), Options::default());
+/// let mut text: String = String::new();
+/// reader.read_to_string(&mut text).await.unwrap();
+/// assert_eq!(text, "Follow me: https://nitter.net/Awiteb");
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct RewriteReader<R> {
+    inner: R,
+    rewriter: StreamRewriter,
+    /// The rewritten bytes that are not read yet
+    output: Vec<u8>,
+    position: usize,
+    finished: bool,
+}
+
+impl<R: AsyncRead + Unpin> RewriteReader<R> {
+    /// Create new [`RewriteReader`] of the `inner` reader
+    pub fn new(inner: R, config: Arc<Config>, options: Options) -> Self {
+        Self {
+            inner,
+            rewriter: StreamRewriter::new(config, options),
+            output: Vec::new(),
+            position: 0,
+            finished: false,
+        }
+    }
+
+    /// Returns the inner reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for RewriteReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this: &mut Self = self.get_mut();
+        loop {
+            if this.position < this.output.len() {
+                let len: usize = buf.remaining().min(this.output.len() - this.position);
+                buf.put_slice(&this.output[this.position..this.position + len]);
+                this.position += len;
+                return Poll::Ready(Ok(()));
+            }
+            if this.finished {
+                return Poll::Ready(Ok(()));
+            }
+            let mut chunk: [u8; CHUNK_SIZE] = [0; CHUNK_SIZE];
+            let mut chunk: ReadBuf = ReadBuf::new(&mut chunk);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut chunk))?;
+            this.output = if chunk.filled().is_empty() {
+                this.finished = true;
+                this.rewriter.finish()
+            } else {
+                this.rewriter.push(chunk.filled())
+            }
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            this.position = 0;
+        }
+    }
+}
+
+/// [`AsyncWrite`] adapter that rewrites the bytes before writing them to the inner writer,
+/// see [`StreamRewriter`]
+///
+/// The pending bytes of the last word are written on [`shutdown`], the [`flush`] writes
+/// the complete bytes only
+///
+/// # Example
+/// ```rust
+/// use domain_changer::types::{Config, Options};
+/// use domain_changer::RewriteWriter;
+/// use std::sync::Arc;
+/// use tokio::io::AsyncWriteExt;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let mut writer = RewriteWriter::new(Vec::new(), Arc::new(Config::default()), Options::default());
+/// writer.write_all(b"Follow me: twit").await.unwrap();
+/// writer.write_all(b"ter.com/Awiteb").await.unwrap();
+/// writer.shutdown().await.unwrap();
+/// assert_eq!(writer.into_inner(), b"Follow me: https://nitter.net/Awiteb");
+/// # });
+/// ```
+///
+/// [`shutdown`]: tokio::io::AsyncWriteExt::shutdown
+/// [`flush`]: tokio::io::AsyncWriteExt::flush
+#[derive(Debug)]
+pub struct RewriteWriter<W> {
+    inner: W,
+    rewriter: StreamRewriter,
+    /// The rewritten bytes that are not written yet
+    output: Vec<u8>,
+    position: usize,
+    finished: bool,
+}
+
+impl<W: AsyncWrite + Unpin> RewriteWriter<W> {
+    /// Create new [`RewriteWriter`] of the `inner` writer
+    pub fn new(inner: W, config: Arc<Config>, options: Options) -> Self {
+        Self {
+            inner,
+            rewriter: StreamRewriter::new(config, options),
+            output: Vec::new(),
+            position: 0,
+            finished: false,
+        }
+    }
+
+    /// Returns the inner writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Write the rewritten bytes to the inner writer
+    fn poll_write_output(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.position < self.output.len() {
+            let written: usize =
+                ready!(Pin::new(&mut self.inner).poll_write(cx, &self.output[self.position..]))?;
+            if written == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.position += written;
+        }
+        self.output.clear();
+        self.position = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for RewriteWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this: &mut Self = self.get_mut();
+        ready!(this.poll_write_output(cx))?;
+        this.output = this
+            .rewriter
+            .push(buf)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this: &mut Self = self.get_mut();
+        ready!(this.poll_write_output(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this: &mut Self = self.get_mut();
+        ready!(this.poll_write_output(cx))?;
+        if !this.finished {
+            this.finished = true;
+            this.output = this
+                .rewriter
+                .finish()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            ready!(this.poll_write_output(cx))?;
+        }
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RewriteReader, RewriteWriter};
+    use crate::parse_string;
+    use crate::types::{Config, LimitAction, Limits, Options};
+    use std::io;
+    use std::sync::Arc;
+    use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt};
+
+    const TEXT: &str =
+        "Hi 👋, my channel is https://www.youtube.com/channel/UCeRbJsc8cl7xBwT3jIxaAdg\n\
+        and my twitter is: twitter.com/Awiteb. Bücher.de reddit.com/r/rust";
+
+    #[tokio::test]
+    async fn reader_test() {
+        // Small buffer of the duplex, the urls are split across the chunks
+        for (buffer, chunk) in [(7, 3), (64, 5), (1024, 1024)] {
+            let (mut client, server) = duplex(buffer);
+            let writer = tokio::spawn(async move {
+                for chunk in TEXT.as_bytes().chunks(chunk) {
+                    client.write_all(chunk).await.unwrap();
+                }
+            });
+            let mut reader =
+                RewriteReader::new(server, Arc::new(Config::default()), Options::default());
+            let mut text: String = String::new();
+            reader.read_to_string(&mut text).await.unwrap();
+            writer.await.unwrap();
+            assert_eq!(text, parse_string(&Config::default(), TEXT.to_owned()));
+        }
+    }
+
+    #[tokio::test]
+    async fn writer_test() {
+        for (buffer, chunk) in [(7, 3), (64, 5), (1024, 1024)] {
+            let (client, mut server) = duplex(buffer);
+            let reader = tokio::spawn(async move {
+                let mut text: String = String::new();
+                server.read_to_string(&mut text).await.unwrap();
+                text
+            });
+            let mut writer =
+                RewriteWriter::new(client, Arc::new(Config::default()), Options::default());
+            for chunk in TEXT.as_bytes().chunks(chunk) {
+                writer.write_all(chunk).await.unwrap();
+                writer.flush().await.unwrap();
+            }
+            writer.shutdown().await.unwrap();
+            drop(writer);
+            assert_eq!(
+                reader.await.unwrap(),
+                parse_string(&Config::default(), TEXT.to_owned())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn limit_error_test() {
+        let options: Options = Options {
+            limits: Limits {
+                max_input_bytes: Some(8),
+                exceeded: LimitAction::Error,
+                ..Limits::default()
+            },
+            ..Options::default()
+        };
+        let body: &[u8] = TEXT.as_bytes();
+        let mut reader = RewriteReader::new(body, Arc::new(Config::default()), options.clone());
+        let mut text: String = String::new();
+        assert_eq!(
+            reader.read_to_string(&mut text).await.unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        let mut writer = RewriteWriter::new(Vec::new(), Arc::new(Config::default()), options);
+        assert_eq!(
+            writer.write_all(body).await.unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
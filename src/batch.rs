@@ -0,0 +1,225 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parallel parsing of many texts with [rayon](https://docs.rs/rayon)
+
+use crate::parse;
+use crate::types::{errors::DomainChangerResult, Config, Options, Report};
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, ParallelBridge, ParallelIterator,
+};
+
+/// Same as [`parse_string_with`] but parses the `texts` in parallel against the same `config`,
+/// the order of the results is the order of the `texts`
+///
+/// The `texts` can be a slice or [`Vec`] of strings, see [`parse_strings_iter`] for the iterators
+///
+/// # Example
+/// ```rust
+/// use domain_changer::parse_strings;
+/// use domain_changer::types::{Config, Options};
+///
+/// let texts: Vec<String> = (0..1000).map(|index| format!("twitter.com/{}", index)).collect();
+/// let results: Vec<String> = parse_strings(&Config::default(), &texts, &Options::default());
+/// assert_eq!(results.len(), 1000);
+/// assert_eq!(results[42], "https://nitter.net/42");
+/// assert_eq!(parse_strings(&Config::default(), ["hi", "t.co/x"], &Options::default()), vec!["hi", "https://nitter.net/x"]);
+/// ```
+///
+/// [`parse_string_with`]: crate::parse_string_with
+pub fn parse_strings<I>(config: &Config, texts: I, options: &Options) -> Vec<String>
+where
+    I: IntoParallelIterator,
+    I::Iter: IndexedParallelIterator,
+    I::Item: AsRef<str>,
+{
+    texts
+        .into_par_iter()
+        .map(|text| {
+            let text: &str = text.as_ref();
+            parse(config, text, options).map_or_else(|_| text.to_owned(), |report| report.text)
+        })
+        .collect()
+}
+
+/// Same as [`parse_strings`] but returns the [`Report`] of every text, see [`parse_string_report`]
+///
+/// # Example
+/// ```rust
+/// use domain_changer::parse_strings_report;
+/// use domain_changer::types::{Config, Limits, Options};
+///
+/// let options: Options = Options {
+///     limits: Limits {
+///         max_input_bytes: Some(16),
+///         ..Limits::untrusted()
+///     },
+///     ..Options::default()
+/// };
+/// let reports = parse_strings_report(&Config::default(), ["t.co/x", "t.co/a-longer-text"], &options);
+/// assert_eq!(reports[0].as_ref().unwrap().text, "https://nitter.net/x");
+/// assert!(reports[0].as_ref().unwrap().warnings.is_empty());
+/// assert_eq!(reports[1].as_ref().unwrap().warnings.len(), 1);
+/// ```
+///
+/// [`parse_string_report`]: crate::parse_string_report
+pub fn parse_strings_report<I>(
+    config: &Config,
+    texts: I,
+    options: &Options,
+) -> Vec<DomainChangerResult<Report>>
+where
+    I: IntoParallelIterator,
+    I::Iter: IndexedParallelIterator,
+    I::Item: AsRef<str>,
+{
+    texts
+        .into_par_iter()
+        .map(|text| parse(config, text.as_ref(), options))
+        .collect()
+}
+
+/// Same as [`parse_strings`] but the `texts` can be any iterator, e.g. the lines of a file.
+/// The texts are parsed while the iterator is consumed, and the results are ordered
+/// after all of them are parsed
+///
+/// # Example
+/// ```rust
+/// use domain_changer::parse_strings_iter;
+/// use domain_changer::types::{Config, Options};
+///
+/// let lines = "t.co/a\nhi\nyoutu.be/b".lines();
+/// assert_eq!(
+///     parse_strings_iter(&Config::default(), lines, &Options::default()),
+///     vec!["https://nitter.net/a", "hi", "https://piped.kavin.rocks/b"]
+/// );
+/// ```
+pub fn parse_strings_iter<I>(config: &Config, texts: I, options: &Options) -> Vec<String>
+where
+    I: IntoIterator,
+    I::IntoIter: Send,
+    I::Item: AsRef<str> + Send,
+{
+    ordered(texts, |text| {
+        parse(config, text, options).map_or_else(|_| text.to_owned(), |report| report.text)
+    })
+}
+
+/// Same as [`parse_strings_report`] but the `texts` can be any iterator, see [`parse_strings_iter`]
+///
+/// # Example
+/// ```rust
+/// use domain_changer::parse_strings_report_iter;
+/// use domain_changer::types::{Config, Options};
+///
+/// let reports = parse_strings_report_iter(&Config::default(), "t.co/a hi".split(' '), &Options::default());
+/// assert_eq!(reports[0].as_ref().unwrap().text, "https://nitter.net/a");
+/// assert_eq!(reports[1].as_ref().unwrap().text, "hi");
+/// ```
+pub fn parse_strings_report_iter<I>(
+    config: &Config,
+    texts: I,
+    options: &Options,
+) -> Vec<DomainChangerResult<Report>>
+where
+    I: IntoIterator,
+    I::IntoIter: Send,
+    I::Item: AsRef<str> + Send,
+{
+    ordered(texts, |text| parse(config, text, options))
+}
+
+/// Map the `texts` in parallel with `f`, the results are in the order of the `texts`
+fn ordered<I, T, F>(texts: I, f: F) -> Vec<T>
+where
+    I: IntoIterator,
+    I::IntoIter: Send,
+    I::Item: AsRef<str> + Send,
+    T: Send,
+    F: Fn(&str) -> T + Sync,
+{
+    let mut results: Vec<(usize, T)> = texts
+        .into_iter()
+        .enumerate()
+        .par_bridge()
+        .map(|(index, text)| (index, f(text.as_ref())))
+        .collect();
+    results.sort_unstable_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_strings, parse_strings_iter, parse_strings_report_iter};
+    use crate::types::{Config, LimitAction, Limits, Options};
+
+    /// More texts than the splits of rayon, the lengths differ so they finish out of order
+    fn texts() -> Vec<String> {
+        (0..2_000)
+            .map(|index| format!("{} t.co/{}", "a ".repeat(index % 29), index))
+            .collect()
+    }
+
+    #[test]
+    fn iter_order_test() {
+        let texts: Vec<String> = texts();
+        let results: Vec<String> =
+            parse_strings_iter(&Config::default(), texts.iter(), &Options::default());
+        assert_eq!(results.len(), texts.len());
+        for (index, result) in results.iter().enumerate() {
+            assert!(
+                result.ends_with(&format!(" https://nitter.net/{}", index)),
+                "{}",
+                index
+            );
+        }
+        assert_eq!(
+            results,
+            parse_strings(&Config::default(), &texts, &Options::default())
+        );
+    }
+
+    #[test]
+    fn report_iter_errors_test() {
+        let options: Options = Options {
+            limits: Limits {
+                max_input_bytes: Some(48),
+                exceeded: LimitAction::Error,
+                ..Limits::default()
+            },
+            ..Options::default()
+        };
+        let texts: Vec<String> = texts();
+        let reports = parse_strings_report_iter(&Config::default(), texts.iter(), &options);
+        assert_eq!(reports.len(), texts.len());
+        for (index, (text, report)) in texts.iter().zip(&reports).enumerate() {
+            // Every error is reported at the index of its text
+            match report {
+                Ok(report) => {
+                    assert!(text.len() <= 48, "{}", index);
+                    assert!(report
+                        .text
+                        .ends_with(&format!(" https://nitter.net/{}", index)));
+                }
+                Err(err) => {
+                    assert!(text.len() > 48, "{}", index);
+                    assert!(err.is_limit_exceeded());
+                }
+            }
+        }
+        assert!(reports.iter().any(Result::is_err));
+        assert!(reports.iter().any(Result::is_ok));
+    }
+}
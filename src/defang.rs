@@ -0,0 +1,110 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Refanging and defanging of the urls, the defanged urls are not clickable,
+//! e.g. `hxxps://twitter[.]com/foo`
+
+use crate::canonical::host_span;
+use alloc::{borrow::Cow, string::String};
+
+/// The obfuscations of the defanged urls and their replacements, they are ASCII
+/// case-insensitive
+const FANGS: &[(&str, &str)] = &[
+    ("[.]", "."),
+    ("(.)", "."),
+    ("{.}", "."),
+    ("[dot]", "."),
+    ("(dot)", "."),
+    ("[:]", ":"),
+    ("[://]", "://"),
+];
+
+/// Refang the defanged `word`, `hxxp`, `[.]`, `(.)`, `{.}`, `[dot]`, `(dot)`, `[:]`
+/// and `[://]` are replaced with the original characters
+///
+/// # Example
+/// ```rust
+/// use domain_changer::refang;
+///
+/// assert_eq!(refang("hxxps://twitter[.]com/foo"), "https://twitter.com/foo");
+/// assert_eq!(refang("HXXP[:]//youtube(.)com"), "HTTP://youtube.com");
+/// assert_eq!(refang("youtube[DOT]com"), "youtube.com");
+/// assert_eq!(refang("youtube.com"), "youtube.com");
+/// ```
+pub fn refang(word: &str) -> Cow<'_, str> {
+    let scheme: bool = word
+        .get(..4)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("hxxp"));
+    if !scheme && !word.contains(['[', '(', '{']) {
+        return Cow::Borrowed(word);
+    }
+
+    let mut refanged: String = String::with_capacity(word.len());
+    let mut rest: &str = word;
+    if scheme {
+        refanged.push_str(&word[..1]);
+        refanged.push_str(if word[1..3] == *"xx" { "tt" } else { "TT" });
+        rest = &word[3..];
+    }
+    'chars: while let Some(chr) = rest.chars().next() {
+        for (fang, replacement) in FANGS {
+            if rest
+                .get(..fang.len())
+                .is_some_and(|part| part.eq_ignore_ascii_case(fang))
+            {
+                refanged.push_str(replacement);
+                rest = &rest[fang.len()..];
+                continue 'chars;
+            }
+        }
+        refanged.push(chr);
+        rest = &rest[chr.len_utf8()..];
+    }
+    Cow::Owned(refanged)
+}
+
+/// Defang the `url`, the `http` scheme is replaced with `hxxp` and the dots
+/// of the host are replaced with `[.]`
+///
+/// # Example
+/// ```rust
+/// use domain_changer::defang;
+///
+/// assert_eq!(defang("https://nitter.net/foo.bar"), "hxxps://nitter[.]net/foo.bar");
+/// assert_eq!(defang("HTTP://nitter.net"), "HXXP://nitter[.]net");
+/// assert_eq!(defang("nitter.net/foo"), "nitter[.]net/foo");
+/// ```
+pub fn defang(url: &str) -> String {
+    let span = host_span(url);
+    let mut defanged: String = String::with_capacity(url.len() + 8);
+    let prefix: &str = &url[..span.start];
+    match prefix.get(..4) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("http") && prefix.contains("://") => {
+            defanged.push_str(&prefix[..1]);
+            defanged.push_str(if prefix[1..3] == *"tt" { "xx" } else { "XX" });
+            defanged.push_str(&prefix[3..]);
+        }
+        _ => defanged.push_str(prefix),
+    }
+    for chr in url[span.clone()].chars() {
+        if chr == '.' {
+            defanged.push_str("[.]");
+        } else {
+            defanged.push(chr);
+        }
+    }
+    defanged.push_str(&url[span.end..]);
+    defanged
+}
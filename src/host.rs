@@ -0,0 +1,94 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Normalization of the hosts through [IDNA (UTS #46)](https://www.unicode.org/reports/tr46/)
+
+use alloc::{borrow::Cow, string::String};
+use idna::AsciiDenyList;
+
+/// Normalize the `host` through IDNA (UTS #46), returns the lowercase ASCII form of it,
+/// the Unicode labels are encoded with punycode (`xn--`).
+/// Returns [`None`] if the `host` is invalid
+///
+/// # Example
+/// ```rust
+/// use domain_changer::normalize_host;
+///
+/// assert_eq!(normalize_host("YouTube.com").unwrap(), "youtube.com");
+/// assert_eq!(normalize_host("Bücher.de").unwrap(), "xn--bcher-kva.de");
+/// assert_eq!(normalize_host("xn--bcher-kva.de").unwrap(), "xn--bcher-kva.de");
+/// assert_eq!(normalize_host("[::1]").unwrap(), "[::1]");
+/// assert!(normalize_host("you tube.com").is_none());
+/// ```
+pub fn normalize_host(host: &str) -> Option<Cow<'_, str>> {
+    if host.starts_with('[') {
+        // IPv6, the `Url` already serialize it in canonical form
+        return Some(Cow::Borrowed(host));
+    }
+    idna::domain_to_ascii_cow(host.as_bytes(), AsciiDenyList::URL)
+        .ok()
+        .filter(|host| !host.is_empty())
+}
+
+/// Returns the Unicode form of the `host`, the punycode (`xn--`) labels are decoded
+///
+/// # Example
+/// ```rust
+/// use domain_changer::host_to_unicode;
+///
+/// assert_eq!(host_to_unicode("xn--bcher-kva.de"), "bücher.de");
+/// assert_eq!(host_to_unicode("nitter.net"), "nitter.net");
+/// ```
+pub fn host_to_unicode(host: &str) -> String {
+    idna::domain_to_unicode(host).0
+}
+
+/// Returns [`true`] if the hosts are the same host after the normalization
+pub(crate) fn same_host(first: &str, second: &str) -> bool {
+    first.eq_ignore_ascii_case(second)
+        || matches!(
+            (normalize_host(first), normalize_host(second)),
+            (Some(first), Some(second)) if first == second
+        )
+}
+
+/// Returns the key of the `host` for the sets and the maps, the hosts that are
+/// the same host after the normalization have the same key, see [`same_host`]
+pub(crate) fn host_key(host: &str) -> String {
+    normalize_host(host).map_or_else(|| host.to_ascii_lowercase(), Cow::into_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{host_key, same_host};
+
+    #[test]
+    fn same_host_test() {
+        assert!(same_host("youtube.com", "YOUTUBE.com"));
+        assert!(same_host("bücher.de", "xn--bcher-kva.de"));
+        assert!(same_host("BÜCHER.de", "xn--bcher-kva.de"));
+        // Fullwidth characters are mapped to ASCII
+        assert!(same_host("ｙｏｕｔｕｂｅ.com", "youtube.com"));
+        assert!(!same_host("youtube.com", "youtu.be"));
+        assert!(!same_host("you tube.com", "youtube.com"));
+    }
+
+    #[test]
+    fn host_key_test() {
+        assert_eq!(host_key("YouTube.com"), host_key("youtube.com"));
+        assert_eq!(host_key("BÜCHER.de"), host_key("xn--bcher-kva.de"));
+        assert_ne!(host_key("youtube.com"), host_key("youtu.be"));
+    }
+}
@@ -0,0 +1,126 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Enforcing of the [`Limits`] while parsing the text
+
+use crate::types::errors::{DomainChangerError, DomainChangerResult};
+use crate::types::{Limit, LimitAction, Limits, Warning, WarningKind};
+use alloc::{format, vec::Vec};
+use core::cell::Cell;
+use core::ops::Range;
+
+/// The remaining input bytes, candidates and rules of the parsing, it is shared by the
+/// chunks of a stream so the limits bound the whole stream
+#[derive(Debug, Clone)]
+pub(crate) struct Budget {
+    input_bytes: Cell<usize>,
+    candidates: Cell<usize>,
+    rules: Cell<usize>,
+    rules_exhausted: Cell<bool>,
+    truncated: Cell<bool>,
+}
+
+impl Budget {
+    pub(crate) fn new(limits: &Limits) -> Self {
+        Self {
+            input_bytes: Cell::new(limits.max_input_bytes.unwrap_or(usize::MAX)),
+            candidates: Cell::new(limits.max_candidates.unwrap_or(usize::MAX)),
+            rules: Cell::new(limits.max_rules.unwrap_or(usize::MAX)),
+            rules_exhausted: Cell::new(false),
+            truncated: Cell::new(false),
+        }
+    }
+
+    /// Returns the remaining input bytes
+    pub(crate) fn input_bytes(&self) -> usize {
+        self.input_bytes.get()
+    }
+
+    /// Take `len` input bytes, the remaining input bytes are never less than zero
+    pub(crate) fn take_input(&self, len: usize) {
+        self.input_bytes
+            .set(self.input_bytes.get().saturating_sub(len));
+    }
+
+    /// Take a candidate url, returns [`false`] if there is no remaining candidates
+    pub(crate) fn candidate(&self) -> bool {
+        take(&self.candidates)
+    }
+
+    /// Take a rule evaluation, returns [`false`] if there is no remaining rules
+    pub(crate) fn rule(&self) -> bool {
+        let taken: bool = take(&self.rules);
+        if !taken {
+            self.rules_exhausted.set(true);
+        }
+        taken
+    }
+
+    /// Returns [`true`] if a rule is not evaluated because of the limit
+    pub(crate) fn rules_exhausted(&self) -> bool {
+        self.rules_exhausted.get()
+    }
+
+    /// Mark the rest of the input as not parsed because of a limit
+    pub(crate) fn truncate(&self) {
+        self.truncated.set(true);
+    }
+
+    /// Returns [`true`] if the rest of the input is not parsed because of a limit
+    pub(crate) fn truncated(&self) -> bool {
+        self.truncated.get()
+    }
+}
+
+fn take(remaining: &Cell<usize>) -> bool {
+    match remaining.get() {
+        0 => false,
+        value => {
+            remaining.set(value - 1);
+            true
+        }
+    }
+}
+
+/// Handle the exceeded `limit` with the [`LimitAction`], returns the error or
+/// reports the `span` that is not parsed because of the limit
+pub(crate) fn exceeded(
+    limits: &Limits,
+    limit: Limit,
+    span: Range<usize>,
+    warnings: &mut Vec<Warning>,
+) -> DomainChangerResult<()> {
+    match limits.exceeded {
+        LimitAction::Error => Err(DomainChangerError::LimitExceeded(format!(
+            "'{}..{}', the {} limit is exceeded",
+            span.start, span.end, limit
+        ))),
+        LimitAction::Truncate => {
+            warnings.push(Warning {
+                span,
+                kind: WarningKind::LimitExceeded(limit),
+            });
+            Ok(())
+        }
+    }
+}
+
+/// Returns the largest char boundary of the `text` that is not greater than `index`
+pub(crate) fn floor_char_boundary(text: &str, index: usize) -> usize {
+    (0..=index.min(text.len()))
+        .rev()
+        .find(|index| text.is_char_boundary(*index))
+        .unwrap_or_default()
+}
@@ -0,0 +1,145 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Detection of the hosts that look like the known hosts of the config,
+//! with the [confusable skeletons](https://www.unicode.org/reports/tr39/#Confusable_Detection)
+//! and the edit distance
+
+use crate::host::{host_to_unicode, same_host};
+use crate::types::{Config, Lookalike, LookalikeReason};
+use alloc::{string::String, vec::Vec};
+use unicode_security::skeleton;
+
+/// The maximum edit distance between the skeletons of the lookalike and the known host
+const MAX_DISTANCE: usize = 1;
+/// The minimum length of the skeleton of the known host to use the edit distance,
+/// the shorter hosts (e.g. `t.co`) are lookalike only if they are confusable
+const MIN_DISTANCE_LENGTH: usize = 6;
+
+/// Detector of the lookalike hosts, it has the skeletons of the known hosts
+pub(crate) struct Detector<'a> {
+    known: Vec<(&'a str, Vec<char>)>,
+}
+
+impl<'a> Detector<'a> {
+    pub(crate) fn new(config: &'a Config) -> Self {
+        let mut known: Vec<(&'a str, Vec<char>)> = Vec::new();
+        for host in config.old_hosts().into_iter().chain(config.new_hosts()) {
+            if !known.iter().any(|(known_host, _)| *known_host == host) {
+                known.push((host, host_skeleton(host)));
+            }
+        }
+        Self { known }
+    }
+
+    /// Returns the [`Lookalike`] of the `host` if it looks like a known host,
+    /// the known host itself is not a lookalike
+    pub(crate) fn check(&self, host: &str) -> Option<Lookalike> {
+        if self.known.iter().any(|(known, _)| same_host(known, host)) {
+            return None;
+        }
+        let skeleton: Vec<char> = host_skeleton(host);
+        self.known.iter().find_map(|(known, known_skeleton)| {
+            let reason: LookalikeReason = if skeleton == *known_skeleton {
+                LookalikeReason::Confusable
+            } else if known_skeleton.len() >= MIN_DISTANCE_LENGTH
+                && skeleton.len().abs_diff(known_skeleton.len()) <= MAX_DISTANCE
+            {
+                let distance: usize = edit_distance(&skeleton, known_skeleton);
+                if distance > MAX_DISTANCE {
+                    return None;
+                }
+                LookalikeReason::EditDistance(distance)
+            } else {
+                return None;
+            };
+            Some(Lookalike {
+                host: host_to_unicode(host),
+                of: host_to_unicode(known),
+                reason,
+            })
+        })
+    }
+}
+
+impl Config {
+    /// Returns the [`Lookalike`] of the `host` if it looks like an [`old`] or a [`new`] host
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, LookalikeReason};
+    ///
+    /// let config: Config = Config::default();
+    /// let lookalike = config.lookalike_of("rеddit.com").unwrap(); // Cyrillic `е`
+    /// assert_eq!((lookalike.of.as_str(), lookalike.reason), ("reddit.com", LookalikeReason::Confusable));
+    /// assert_eq!(
+    ///     config.lookalike_of("yout0be.com").unwrap().reason,
+    ///     LookalikeReason::EditDistance(1)
+    /// );
+    /// assert_eq!(config.lookalike_of("twltter.com").unwrap().of, "twitter.com");
+    /// assert!(config.lookalike_of("twitter.com").is_none());
+    /// assert!(config.lookalike_of("t.cn").is_none());
+    /// ```
+    ///
+    /// [`old`]: crate::types::Domain#structfield.old
+    /// [`new`]: crate::types::Domain#structfield.new
+    pub fn lookalike_of(&self, host: &str) -> Option<Lookalike> {
+        Detector::new(self).check(host)
+    }
+}
+
+/// Returns the lowercase skeleton of the Unicode form of the `host`
+fn host_skeleton(host: &str) -> Vec<char> {
+    let host: String = host_to_unicode(host).to_lowercase();
+    skeleton(&host).flat_map(char::to_lowercase).collect()
+}
+
+/// Returns the Levenshtein distance between `first` and `second`
+fn edit_distance(first: &[char], second: &[char]) -> usize {
+    let mut row: Vec<usize> = (0..=second.len()).collect();
+    for (index, first_chr) in first.iter().enumerate() {
+        let mut previous: usize = row[0];
+        row[0] = index + 1;
+        for (second_index, second_chr) in second.iter().enumerate() {
+            let substitution: usize = previous + usize::from(first_chr != second_chr);
+            previous = row[second_index + 1];
+            row[second_index + 1] = substitution.min(row[second_index] + 1).min(previous + 1);
+        }
+    }
+    row[second.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::edit_distance;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn edit_distance_test() {
+        let chars = |text: &str| text.chars().collect::<Vec<char>>();
+        for (first, second, distance) in [
+            ("", "", 0),
+            ("youtube", "youtube", 0),
+            ("youtube", "yout0be", 1),
+            ("youtube", "youtub", 1),
+            ("youtube", "yyoutube", 1),
+            ("kitten", "sitting", 3),
+            ("", "abc", 3),
+        ] {
+            assert_eq!(edit_distance(&chars(first), &chars(second)), distance);
+            assert_eq!(edit_distance(&chars(second), &chars(first)), distance);
+        }
+    }
+}
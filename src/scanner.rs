@@ -0,0 +1,281 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Detection of the words boundaries in the text
+//!
+//! The words are separated by the Unicode whitespace, the format characters
+//! (e.g. the right-to-left marks) and the non-ASCII punctuation (e.g. `،`, `؟`, `。`, `（`),
+//! then the ASCII punctuation around the word (e.g. `(`, `,` and `.`) is trimmed
+
+use core::ops::Range;
+use unicode_general_category::{get_general_category, GeneralCategory};
+
+/// The label separators of IDNA (UTS #46) that are punctuation, they are a part
+/// of the word when they are between two characters of it
+const IDNA_DOTS: [char; 3] = ['\u{3002}', '\u{FF0E}', '\u{FF61}'];
+
+/// Iterator over the ranges of the words in the text, see [module](self) documentation
+pub(crate) struct Words<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Words<'a> {
+    pub(crate) fn new(text: &'a str) -> Self {
+        Self { text, position: 0 }
+    }
+}
+
+impl Iterator for Words<'_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.position < self.text.len() {
+            let rest: &str = &self.text[self.position..];
+            let start: usize = self.position
+                + rest
+                    .char_indices()
+                    .find(|(_, chr)| !is_boundary(*chr))
+                    .map_or(rest.len(), |(index, _)| index);
+            let mut chars = self.text[start..].char_indices().peekable();
+            let mut end: usize = self.text.len();
+            let mut previous: Option<char> = None;
+            while let Some((index, chr)) = chars.next() {
+                let is_inner_dot = IDNA_DOTS.contains(&chr)
+                    && chars.peek().is_some_and(|(_, next)| !is_boundary(*next));
+                if is_boundary(chr)
+                    && !is_inner_dot
+                    && !is_inner_invisible(self.text, start + index, previous)
+                {
+                    end = start + index;
+                    break;
+                }
+                if !is_invisible(chr) {
+                    previous = Some(chr);
+                }
+            }
+            self.position = end;
+
+            let word: Range<usize> = trim(self.text, start..end);
+            if !word.is_empty() {
+                return Some(word);
+            }
+        }
+        None
+    }
+}
+
+/// Returns the start of the word that crosses the `index`, or the `index` if it is
+/// between two words. The invisible characters and the IDNA dots are considered
+/// a part of the word, so the returned start is never after the start that [`Words`] finds
+pub(crate) fn crossing_word_start(text: &str, index: usize) -> usize {
+    let is_word = |chr: char| !is_boundary(chr) || is_invisible(chr) || IDNA_DOTS.contains(&chr);
+    if !text[index..].chars().next().is_some_and(is_word) {
+        return index;
+    }
+    text[..index]
+        .char_indices()
+        .rev()
+        .find(|(_, chr)| !is_word(*chr))
+        .map_or(0, |(start, chr)| start + chr.len_utf8())
+}
+
+/// Returns [`true`] if the `word` is a candidate url, it has a dot or a scheme
+pub(crate) fn is_candidate(word: &str) -> bool {
+    word.contains(|chr| chr == '.' || IDNA_DOTS.contains(&chr)) || word.contains("://")
+}
+
+/// Returns [`true`] if the `chr` is an invisible character, the zero-width characters,
+/// the bidi marks, embeddings, overrides and isolates, e.g. `U+200B` and `U+202E`
+pub(crate) fn is_invisible(chr: char) -> bool {
+    matches!(
+        chr,
+        '\u{AD}'
+            | '\u{61C}'
+            | '\u{180E}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{FEFF}'
+    )
+}
+
+/// Returns [`true`] if the invisible character at `index` is inside a url, the
+/// characters around it (`previous` and the next visible one) are ASCII graphic
+/// characters and it is not a bidi mark, e.g. `twitter\u{200B}.com` but not
+/// `شاهد\u{202E}youtube.com` or `youtube.com/x\u{200F}.`
+fn is_inner_invisible(text: &str, index: usize, previous: Option<char>) -> bool {
+    let mut rest = text[index..].chars();
+    // The bidi marks are used around the urls in right-to-left text
+    rest.next()
+        .is_some_and(|chr| is_invisible(chr) && !matches!(chr, '\u{200E}' | '\u{200F}' | '\u{61C}'))
+        && previous.is_some_and(|chr| chr.is_ascii_graphic())
+        && rest
+            .find(|chr| !is_invisible(*chr))
+            .is_some_and(|chr| chr.is_ascii_graphic())
+}
+
+/// Returns [`true`] if the `chr` separate the words
+fn is_boundary(chr: char) -> bool {
+    chr.is_whitespace()
+        || !chr.is_ascii()
+            && matches!(
+                get_general_category(chr),
+                GeneralCategory::Format
+                    | GeneralCategory::Control
+                    | GeneralCategory::LineSeparator
+                    | GeneralCategory::ParagraphSeparator
+                    | GeneralCategory::SpaceSeparator
+                    | GeneralCategory::OpenPunctuation
+                    | GeneralCategory::ClosePunctuation
+                    | GeneralCategory::InitialPunctuation
+                    | GeneralCategory::FinalPunctuation
+                    | GeneralCategory::OtherPunctuation
+                    | GeneralCategory::DashPunctuation
+                    | GeneralCategory::ConnectorPunctuation
+            )
+}
+
+/// The ASCII brackets, the opening and the closing one
+const BRACKETS: [(u8, u8); 4] = [(b'(', b')'), (b'[', b']'), (b'{', b'}'), (b'<', b'>')];
+
+/// Trim the ASCII punctuation around the word, the closing brackets are trimmed
+/// only if they are not opened in the word, e.g. `(youtube.com/watch_(video))`
+fn trim(text: &str, word: Range<usize>) -> Range<usize> {
+    let mut start: usize = word.start;
+    let mut end: usize = word.end;
+    while let Some(chr) = text[start..end].chars().next() {
+        if chr.is_ascii_punctuation() {
+            start += 1;
+        } else {
+            break;
+        }
+    }
+    // The opened and closed brackets of the word, they are counted once and the
+    // closed ones are decremented while trimming
+    let mut brackets: [(usize, usize); 4] = [(0, 0); 4];
+    for byte in text[start..end].bytes() {
+        for ((opener, closer), (opened, closed)) in BRACKETS.iter().zip(brackets.iter_mut()) {
+            if byte == *opener {
+                *opened += 1;
+            } else if byte == *closer {
+                *closed += 1;
+            }
+        }
+    }
+    while let Some(chr) = text[start..end].chars().next_back() {
+        let trimmed: bool = match chr {
+            '.' | ',' | ';' | ':' | '!' | '?' | '\'' | '"' | '*' => true,
+            ')' | ']' | '}' | '>' => {
+                let (opened, closed) = &mut brackets[match chr {
+                    ')' => 0,
+                    ']' => 1,
+                    '}' => 2,
+                    _ => 3,
+                }];
+                if *opened < *closed {
+                    *closed -= 1;
+                    true
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        };
+        if !trimmed {
+            break;
+        }
+        end -= 1;
+    }
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crossing_word_start, Words};
+    use alloc::vec::Vec;
+
+    fn words(text: &str) -> Vec<&str> {
+        Words::new(text).map(|word| &text[word]).collect()
+    }
+
+    #[test]
+    fn words_test() {
+        assert_eq!(words(""), Vec::<&str>::new());
+        assert_eq!(words(" \t\n "), Vec::<&str>::new());
+        assert_eq!(
+            words("Hello, (youtube.com/x) and twitter.com/Awiteb."),
+            ["Hello", "youtube.com/x", "and", "twitter.com/Awiteb"]
+        );
+        assert_eq!(
+            words("see (en.wikipedia.org/wiki/Rust_(language)), <t.co/x>"),
+            ["see", "en.wikipedia.org/wiki/Rust_(language)", "t.co/x"]
+        );
+        assert_eq!(words("((t.co/a_(b)_[c])))]>."), ["t.co/a_(b)_[c]"]);
+        // non-breaking, ideographic and zero width spaces
+        assert_eq!(
+            words("a\u{a0}youtube.com\u{3000}b \u{200b}t.co"),
+            ["a", "youtube.com", "b", "t.co"]
+        );
+    }
+
+    #[test]
+    fn words_mixed_script_test() {
+        // Arabic comma, question mark and semicolon, and the right-to-left marks
+        assert_eq!(
+            words("\u{200f}شاهد youtube.com/x\u{60c} أو twitter.com\u{61f}\u{200f} نعم\u{61b}t.co"),
+            ["شاهد", "youtube.com/x", "أو", "twitter.com", "نعم", "t.co"]
+        );
+        // Arabic letters with harakat are one word
+        assert_eq!(words("مَرْحَبًا"), ["مَرْحَبًا"]);
+        // CJK full stop, full-width brackets and comma
+        assert_eq!(
+            words("请看（youtube.com/x）。链接：twitter.com，谢谢。"),
+            ["请看", "youtube.com/x", "链接", "twitter.com", "谢谢"]
+        );
+        // Full-width and ideographic dots inside the host
+        assert_eq!(words("ｙｏｕｔｕｂｅ．com。"), ["ｙｏｕｔｕｂｅ．com"]);
+        assert_eq!(words("「bücher.de」"), ["bücher.de"]);
+    }
+
+    #[test]
+    fn words_invisible_test() {
+        assert_eq!(
+            words("twitter\u{200b}.com/x youtube.com/\u{202e}\u{2066}exe.txt"),
+            [
+                "twitter\u{200b}.com/x",
+                "youtube.com/\u{202e}\u{2066}exe.txt"
+            ]
+        );
+        // Around the words, or between non-ASCII characters
+        assert_eq!(
+            words("\u{202e}t.co\u{200b} شاهد\u{202e}youtube.com/x\u{200f}. می\u{200c}خواهم"),
+            ["t.co", "شاهد", "youtube.com/x", "می", "خواهم"]
+        );
+    }
+
+    #[test]
+    fn crossing_word_start_test() {
+        let text: &str = "see youtube.com/watch?v=1, ok";
+        assert_eq!(crossing_word_start(text, 15), 4);
+        assert_eq!(crossing_word_start(text, 4), 4);
+        assert_eq!(crossing_word_start(text, 3), 3);
+        assert_eq!(crossing_word_start(text, 26), 26);
+        assert_eq!(crossing_word_start(text, text.len()), text.len());
+        assert_eq!(crossing_word_start("t.co", 2), 0);
+        assert_eq!(crossing_word_start("a twitter\u{200b}.com", 12), 2);
+    }
+}
@@ -0,0 +1,311 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Incremental rewriting of a text that comes in chunks, e.g. the body of a response
+
+use crate::limits::{exceeded, Budget};
+use crate::parse_budgeted;
+use crate::scanner::is_candidate;
+use crate::types::errors::DomainChangerResult;
+use crate::types::{Config, Limit, Options, Report};
+use alloc::{string::String, sync::Arc, vec::Vec};
+
+/// The default maximum length of the pending word, if [`Limits::max_url_length`] is not set
+///
+/// [`Limits::max_url_length`]: crate::types::Limits#structfield.max_url_length
+const DEFAULT_MAX_PENDING: usize = 64 * 1024;
+
+/// Incremental rewriter of a byte stream, the chunks are pushed to it and the rewritten bytes
+/// are returned as soon as they are complete
+///
+/// The bytes after the last ASCII whitespace are pending until the next chunk, so the urls that
+/// are split across the chunks are rewritten like the text is one chunk. The invalid UTF-8 bytes
+/// are written as is. The [`Options::limits`] bound the whole stream, not every chunk of it,
+/// after a limit is exceeded with [`LimitAction::Truncate`] the rest of the stream is written
+/// as is. The pending word that is longer than [`Limits::max_url_length`] (64KiB by default)
+/// is written as is without waiting for the rest of it, and the rest of it is written as is too
+///
+/// # Example
+/// ```rust
+/// use domain_changer::StreamRewriter;
+/// use domain_changer::types::{Config, Options};
+/// use std::sync::Arc;
+///
+/// let mut rewriter = StreamRewriter::new(Arc::new(Config::default()), Options::default());
+/// let mut output: Vec<u8> = rewriter.push(b"Follow me: twit").unwrap();
+/// assert_eq!(output, b"Follow me: ");
+/// output.extend(rewriter.push(b"ter.com/Awi").unwrap());
+/// output.extend(rewriter.push(b"teb \xF0\x9F").unwrap());
+/// output.extend(rewriter.push(b"\x98\x80").unwrap());
+/// output.extend(rewriter.finish().unwrap());
+/// assert_eq!(String::from_utf8(output).unwrap(), "Follow me: https://nitter.net/Awiteb 😀");
+/// ```
+///
+/// [`Options::limits`]: crate::types::Options#structfield.limits
+/// [`LimitAction::Truncate`]: crate::types::LimitAction::Truncate
+/// [`Limits::max_url_length`]: crate::types::Limits#structfield.max_url_length
+#[derive(Debug, Clone)]
+pub struct StreamRewriter {
+    config: Arc<Config>,
+    options: Options,
+    budget: Budget,
+    pending: Vec<u8>,
+    /// Is the rest of the long pending word written as is
+    overflowed: bool,
+}
+
+impl StreamRewriter {
+    /// Create new [`StreamRewriter`]
+    pub fn new(config: Arc<Config>, options: Options) -> Self {
+        Self {
+            config,
+            budget: Budget::new(&options.limits),
+            options,
+            pending: Vec::new(),
+            overflowed: false,
+        }
+    }
+
+    /// Push the next `chunk` of the stream, returns the rewritten bytes that are complete
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`] error if a limit of [`Options::limits`] is exceeded with
+    /// [`LimitAction::Error`], the bytes of the chunk are not written
+    ///
+    /// [`LimitExceeded`]: crate::types::errors::DomainChangerError::LimitExceeded
+    /// [`Options::limits`]: crate::types::Options#structfield.limits
+    /// [`LimitAction::Error`]: crate::types::LimitAction::Error
+    pub fn push(&mut self, mut chunk: &[u8]) -> DomainChangerResult<Vec<u8>> {
+        let mut output: Vec<u8> = Vec::with_capacity(self.pending.len() + chunk.len());
+        if self.overflowed {
+            // The rest of the long word, up to the next ASCII whitespace
+            let end: usize = chunk
+                .iter()
+                .position(u8::is_ascii_whitespace)
+                .unwrap_or(chunk.len());
+            self.write_as_is(&chunk[..end], &mut output);
+            self.overflowed = end == chunk.len();
+            chunk = &chunk[end..];
+        }
+        self.pending.extend_from_slice(chunk);
+        // The ASCII bytes are never part of multi-byte UTF-8 character
+        if let Some(index) = self.pending.iter().rposition(u8::is_ascii_whitespace) {
+            let rest: Vec<u8> = self.pending.split_off(index + 1);
+            let complete: Vec<u8> = core::mem::replace(&mut self.pending, rest);
+            self.rewrite(&complete, &mut output)?;
+        }
+        let max_url_length: Option<usize> = self.options.limits.max_url_length;
+        if self.pending.len() > max_url_length.unwrap_or(DEFAULT_MAX_PENDING) {
+            let pending: Vec<u8> = core::mem::take(&mut self.pending);
+            if max_url_length.is_some() && is_candidate(&String::from_utf8_lossy(&pending)) {
+                exceeded(
+                    &self.options.limits,
+                    Limit::UrlLength,
+                    0..pending.len(),
+                    &mut Vec::new(),
+                )?;
+            }
+            self.write_as_is(&pending, &mut output);
+            self.overflowed = true;
+        }
+        Ok(output)
+    }
+
+    /// Finish the stream, returns the rewritten pending bytes
+    ///
+    /// # Errors
+    /// Same as [`push`](StreamRewriter::push)
+    pub fn finish(&mut self) -> DomainChangerResult<Vec<u8>> {
+        let mut output: Vec<u8> = Vec::with_capacity(self.pending.len());
+        let pending: Vec<u8> = core::mem::take(&mut self.pending);
+        self.rewrite(&pending, &mut output)?;
+        Ok(output)
+    }
+
+    /// Rewrite the `bytes` to the `output`, the invalid UTF-8 bytes are written as is
+    fn rewrite(&self, mut bytes: &[u8], output: &mut Vec<u8>) -> DomainChangerResult<()> {
+        while !bytes.is_empty() {
+            if self.budget.truncated() {
+                self.write_as_is(bytes, output);
+                break;
+            }
+            let (text, invalid) = match core::str::from_utf8(bytes) {
+                Ok(text) => (text, 0),
+                Err(err) => (
+                    core::str::from_utf8(&bytes[..err.valid_up_to()]).unwrap_or_default(),
+                    err.error_len().unwrap_or(bytes.len() - err.valid_up_to()),
+                ),
+            };
+            let report: Report = parse_budgeted(&self.config, text, &self.options, &self.budget)?;
+            output.extend_from_slice(report.text.as_bytes());
+            self.write_as_is(&bytes[text.len()..text.len() + invalid], output);
+            bytes = &bytes[text.len() + invalid..];
+        }
+        Ok(())
+    }
+
+    /// Write the `bytes` to the `output` without parsing them, they are counted in the input bytes
+    fn write_as_is(&self, bytes: &[u8], output: &mut Vec<u8>) {
+        self.budget.take_input(bytes.len());
+        output.extend_from_slice(bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreamRewriter;
+    use crate::parse_string_with;
+    use crate::types::{Config, LimitAction, Limits, Options};
+    use alloc::{string::String, sync::Arc, vec::Vec};
+
+    const TEXT: &str =
+        "Hi 👋, my channel is https://www.youtube.com/channel/UCeRbJsc8cl7xBwT3jIxaAdg\n\
+        and my twitter is: twitter.com/Awiteb. Bücher.de reddit.com/r/rust";
+
+    fn rewrite(chunks: &[&[u8]], options: Options) -> Vec<u8> {
+        let mut rewriter = StreamRewriter::new(Arc::new(Config::default()), options);
+        let mut output: Vec<u8> = Vec::new();
+        for chunk in chunks {
+            output.extend(rewriter.push(chunk).unwrap());
+        }
+        output.extend(rewriter.finish().unwrap());
+        output
+    }
+
+    #[test]
+    fn chunk_boundaries_test() {
+        let expected: String =
+            parse_string_with(&Config::default(), TEXT.into(), &Options::default());
+        let bytes: &[u8] = TEXT.as_bytes();
+        // Every split point, including the points inside the multi-byte characters
+        for index in 0..=bytes.len() {
+            let (first, second) = bytes.split_at(index);
+            assert_eq!(
+                String::from_utf8(rewrite(&[first, second], Options::default())).unwrap(),
+                expected,
+                "{}",
+                index
+            );
+        }
+        let chunks: Vec<&[u8]> = bytes.chunks(3).collect();
+        assert_eq!(
+            String::from_utf8(rewrite(&chunks, Options::default())).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn invalid_utf8_test() {
+        assert_eq!(
+            rewrite(
+                &[b"\xFFtwitter.com/a \xC3", b"(t.co/b)\xFE"],
+                Options::default()
+            ),
+            b"\xFFhttps://nitter.net/a \xC3(https://nitter.net/b)\xFE"
+        );
+    }
+
+    #[test]
+    fn max_pending_test() {
+        let options: Options = Options {
+            limits: Limits {
+                max_url_length: Some(8),
+                ..Limits::default()
+            },
+            ..Options::default()
+        };
+        let mut rewriter = StreamRewriter::new(Arc::new(Config::default()), options.clone());
+        assert_eq!(rewriter.push(b"t.co/a").unwrap(), b"");
+        // The pending word is longer than the limit, it is written as is with the rest of it
+        assert_eq!(rewriter.push(b"aaaaaaa\xC3").unwrap(), b"t.co/aaaaaaaa\xC3");
+        assert_eq!(rewriter.push(b"\xA9.com t.co/b").unwrap(), b"\xA9.com ");
+        assert_eq!(rewriter.finish().unwrap(), b"https://nitter.net/b");
+        assert_eq!(
+            rewrite(&[b"t.co/a t.co/b"], options),
+            b"https://nitter.net/a https://nitter.net/b"
+        );
+    }
+
+    #[test]
+    fn default_max_pending_test() {
+        let mut rewriter = StreamRewriter::new(Arc::new(Config::default()), Options::default());
+        let mut word: Vec<u8> = b"twitter.com/".to_vec();
+        word.resize(super::DEFAULT_MAX_PENDING + 1, b'a');
+        // The prefix of the long word is not rewritten without the url length limit
+        assert_eq!(rewriter.push(&word).unwrap(), word);
+        assert_eq!(rewriter.push(b"a t.co/a").unwrap(), b"a ");
+        assert_eq!(rewriter.finish().unwrap(), b"https://nitter.net/a");
+    }
+
+    #[test]
+    fn stream_limits_test() {
+        let options: Options = Options {
+            limits: Limits {
+                max_candidates: Some(2),
+                ..Limits::default()
+            },
+            ..Options::default()
+        };
+        // The limits bound the whole stream, not every chunk of it
+        assert_eq!(
+            rewrite(
+                &[b"t.co/a ", b"t.co/b ", b"t.co/c ", b"t.co/d"],
+                options.clone()
+            ),
+            b"https://nitter.net/a https://nitter.net/b t.co/c t.co/d"
+        );
+        let options: Options = Options {
+            limits: Limits {
+                max_input_bytes: Some(8),
+                ..Limits::default()
+            },
+            ..Options::default()
+        };
+        assert_eq!(
+            rewrite(&[b"t.co/a ", b"t.co/b ", b"t.co/c"], options),
+            b"https://nitter.net/a t.co/b t.co/c"
+        );
+    }
+
+    #[test]
+    fn stream_limit_error_test() {
+        let options: Options = Options {
+            limits: Limits {
+                max_candidates: Some(1),
+                exceeded: LimitAction::Error,
+                ..Limits::default()
+            },
+            ..Options::default()
+        };
+        let mut rewriter = StreamRewriter::new(Arc::new(Config::default()), options.clone());
+        assert_eq!(rewriter.push(b"t.co/a ").unwrap(), b"https://nitter.net/a ");
+        assert!(rewriter
+            .push(b"t.co/b ")
+            .is_err_and(|err| err.is_limit_exceeded()));
+
+        let options: Options = Options {
+            limits: Limits {
+                max_url_length: Some(8),
+                exceeded: LimitAction::Error,
+                ..Limits::default()
+            },
+            ..Options::default()
+        };
+        let mut rewriter = StreamRewriter::new(Arc::new(Config::default()), options);
+        assert!(rewriter
+            .push(b"t.co/aaaaaaaa")
+            .is_err_and(|err| err.is_limit_exceeded()));
+    }
+}
@@ -0,0 +1,47 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Instrumentation of the matching and rewriting with [tracing](https://docs.rs/tracing),
+//! the macros expand to nothing without the `tracing` feature
+//!
+//! The `parse` span has the length of the text, the `word` span has the candidate word,
+//! and the events have the evaluated rule (the [`old`] url of the domain) and the `decision` about the word:
+//! - `too_long`: the word is longer than the url length limit
+//! - `invisible_rejected`: the word has invisible characters and it is rejected
+//! - `email_skipped`: the word is email address and the emails are not rewritten
+//! - `not_url`: the word is not a url after the canonicalization
+//! - `no_host`: the url has no host (`host_str()` is `None`)
+//! - `no_match`: there is no active domain with the host of the url
+//! - `rules_exhausted`: the rules limit is exceeded before the match
+//! - `target_without_host`: the matched domain has no new host
+//! - `rewritten`: the url is rewritten
+//!
+//! [`old`]: crate::types::Domain#structfield.old
+
+/// Emit [`tracing::event!`] if the `tracing` feature is enabled
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::event!($($arg)*);
+    };
+}
+
+/// Enter [`tracing::span!`] until the end of the scope if the `tracing` feature is enabled
+macro_rules! trace_span {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::span!($($arg)*).entered();
+    };
+}
@@ -0,0 +1,276 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Compact binary form of [`Config`]
+//!
+//! The blob is `MAGIC (4 bytes) | version (1 byte) | crc32 of the payload (4 bytes, LE) | payload`,
+//! the payload is [postcard](https://docs.rs/postcard) encoded table of the unique urls, the domains
+//! as indices of the table, and the groups. Every unique url is parsed once on load, the configs
+//! compiled from services repeat the same frontends and hosts in many domains
+
+use super::errors::{DomainChangerError, DomainChangerResult};
+use crate::types::{Config, Domain, Group};
+use alloc::{borrow::ToOwned, collections::BTreeMap, format, string::String, vec::Vec};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// The magic bytes of the blob
+const MAGIC: &[u8; 4] = b"DCCB";
+/// The current version of the binary format
+const BINARY_VERSION: u8 = 1;
+/// The length of the blob header
+const HEADER_LEN: usize = MAGIC.len() + 1 + 4;
+
+#[derive(Serialize, Deserialize)]
+struct Blob<'a> {
+    /// The unique urls of the domains and the frontends of the groups
+    #[serde(borrow)]
+    urls: Vec<&'a str>,
+    /// The old and new urls of the domains, indices of [`Blob::urls`]
+    domains: Vec<(u32, u32)>,
+    #[serde(borrow)]
+    groups: Vec<BlobGroup<'a>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BlobGroup<'a> {
+    name: &'a str,
+    #[serde(borrow)]
+    tags: Vec<&'a str>,
+    #[serde(borrow)]
+    hosts: Vec<&'a str>,
+    enabled: bool,
+    /// The frontends of the group, indices of [`Blob::urls`]
+    frontends: Vec<u32>,
+}
+
+/// The table of the unique urls of the blob
+#[derive(Default)]
+struct UrlTable<'a> {
+    indices: BTreeMap<&'a str, u32>,
+    urls: Vec<&'a str>,
+}
+
+impl<'a> UrlTable<'a> {
+    /// Returns the index of the `url` in the table, it is added if it is not in the table
+    fn index(&mut self, url: &'a Url) -> u32 {
+        *self.indices.entry(url.as_str()).or_insert_with(|| {
+            self.urls.push(url.as_str());
+            // The config can't have more than `u32::MAX` urls in practice
+            (self.urls.len() - 1) as u32
+        })
+    }
+}
+
+impl Config {
+    /// Serialize [`Config`] to compact and versioned binary blob
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Config;
+    ///
+    /// let config: Config = Config::default();
+    /// let bytes: Vec<u8> = config.to_bytes();
+    /// assert_eq!(Config::from_bytes(&bytes).unwrap(), config);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut table: UrlTable = UrlTable::default();
+        let domains: Vec<(u32, u32)> = self
+            .domains
+            .iter()
+            .map(|domain| (table.index(&domain.old), table.index(&domain.new)))
+            .collect();
+        let groups: Vec<BlobGroup> = self
+            .groups
+            .iter()
+            .map(|group| BlobGroup {
+                name: &group.name,
+                tags: group.tags.iter().map(String::as_str).collect(),
+                hosts: group.hosts.iter().map(String::as_str).collect(),
+                enabled: group.enabled,
+                frontends: group
+                    .frontends
+                    .iter()
+                    .map(|frontend| table.index(frontend))
+                    .collect(),
+            })
+            .collect();
+        let blob: Blob = Blob {
+            urls: table.urls,
+            domains,
+            groups,
+        };
+        // Serializing to `Vec` can't fail, the blob has no maps or unsupported types
+        let payload: Vec<u8> = postcard::to_allocvec(&blob).unwrap();
+
+        let mut bytes: Vec<u8> = Vec::with_capacity(HEADER_LEN + payload.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(BINARY_VERSION);
+        bytes.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+        bytes.extend_from_slice(&payload);
+        bytes
+    }
+
+    /// Deserialize [`Config`] from binary blob, see [`Config.to_bytes`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Config;
+    ///
+    /// let mut bytes: Vec<u8> = Config::default().to_bytes();
+    /// let last = bytes.len() - 1;
+    /// bytes[last] ^= 0xff;
+    /// assert!(Config::from_bytes(&bytes).unwrap_err().is_invalid_binary());
+    /// assert!(Config::from_bytes(b"DCCB").unwrap_err().is_invalid_binary());
+    /// ```
+    ///
+    /// [`Config.to_bytes`]: method@Config::to_bytes
+    pub fn from_bytes(bytes: &[u8]) -> DomainChangerResult<Self> {
+        if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(invalid_binary("the bytes are not a config blob"));
+        }
+        let version: u8 = bytes[MAGIC.len()];
+        if version != BINARY_VERSION {
+            return Err(invalid_binary(&format!(
+                "'{}', unsupported binary version, expected '{}'",
+                version, BINARY_VERSION
+            )));
+        }
+        let checksum: [u8; 4] = bytes[MAGIC.len() + 1..HEADER_LEN].try_into().unwrap();
+        let payload: &[u8] = &bytes[HEADER_LEN..];
+        if crc32fast::hash(payload) != u32::from_le_bytes(checksum) {
+            return Err(invalid_binary("checksum mismatch, the blob is corrupted"));
+        }
+
+        let blob: Blob = take_payload(payload)?;
+        let urls: Vec<Url> = blob
+            .urls
+            .into_iter()
+            .map(parse_url)
+            .collect::<DomainChangerResult<_>>()?;
+        let url = |index: u32| {
+            urls.get(index as usize)
+                .cloned()
+                .ok_or_else(|| invalid_binary(&format!("'{}', url index out of the table", index)))
+        };
+        let domains: Vec<Domain> = blob
+            .domains
+            .into_iter()
+            .map(|(old, new)| Ok(Domain::new(url(old)?, url(new)?)))
+            .collect::<DomainChangerResult<_>>()?;
+        let groups: Vec<Group> = blob
+            .groups
+            .into_iter()
+            .map(|group| {
+                Ok(Group {
+                    name: group.name.to_owned(),
+                    tags: group.tags.into_iter().map(String::from).collect(),
+                    hosts: group.hosts.into_iter().map(String::from).collect(),
+                    enabled: group.enabled,
+                    frontends: group
+                        .frontends
+                        .into_iter()
+                        .map(url)
+                        .collect::<DomainChangerResult<_>>()?,
+                })
+            })
+            .collect::<DomainChangerResult<_>>()?;
+        Ok(Config::with_groups(domains, groups))
+    }
+}
+
+/// Decode the `payload`, it should be decoded to the end
+fn take_payload<'a, T: Deserialize<'a>>(payload: &'a [u8]) -> DomainChangerResult<T> {
+    let (blob, rest): (T, &[u8]) = postcard::take_from_bytes(payload)
+        .map_err(|err| invalid_binary(&format!("invalid payload, {}", err)))?;
+    if !rest.is_empty() {
+        return Err(invalid_binary("trailing bytes after the payload"));
+    }
+    Ok(blob)
+}
+
+fn parse_url(url: &str) -> DomainChangerResult<Url> {
+    Url::parse(url).map_err(|_| invalid_binary(&format!("'{}', invalid url in the payload", url)))
+}
+
+fn invalid_binary(msg: &str) -> DomainChangerError {
+    DomainChangerError::InvalidBinary(msg.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{Config, Service};
+    use alloc::{vec, vec::Vec};
+    use url::Url;
+
+    #[test]
+    fn binary_roundtrip_test() {
+        let mut config: Config = Config::default();
+        config.disable_group("reddit");
+        assert_eq!(Config::from_bytes(&config.to_bytes()).unwrap(), config);
+
+        let empty: Config = Config::new(Vec::new());
+        assert_eq!(Config::from_bytes(&empty.to_bytes()).unwrap(), empty);
+
+        let services: Config = Config::from_services(vec![Service::new(
+            "youtube",
+            &["video"],
+            &["youtube.com", "youtu.be"],
+            vec![
+                Url::parse("https://piped.kavin.rocks/").unwrap(),
+                Url::parse("https://yewtu.be/").unwrap(),
+            ],
+        )])
+        .unwrap();
+        assert_eq!(Config::from_bytes(&services.to_bytes()).unwrap(), services);
+    }
+
+    #[test]
+    fn binary_shared_urls_test() {
+        use super::{Blob, HEADER_LEN};
+
+        let config: Config = Config::builder()
+            .mapping("https://youtube.com/", "https://piped.kavin.rocks/")
+            .mapping("https://youtu.be/", "https://piped.kavin.rocks/")
+            .mapping("https://youtube.com/", "https://yewtu.be/")
+            .build()
+            .unwrap();
+        let bytes: Vec<u8> = config.to_bytes();
+        let blob: Blob = postcard::from_bytes(&bytes[HEADER_LEN..]).unwrap();
+        assert_eq!(blob.urls.len(), 4);
+        assert_eq!(blob.domains, vec![(0, 1), (2, 1), (0, 3)]);
+        assert_eq!(Config::from_bytes(&bytes).unwrap(), config);
+    }
+
+    #[test]
+    fn binary_corrupted_test() {
+        let bytes: Vec<u8> = Config::default().to_bytes();
+
+        // Every single bit flip is rejected
+        for index in 0..bytes.len() {
+            let mut corrupted: Vec<u8> = bytes.clone();
+            corrupted[index] ^= 0b0000_0100;
+            assert!(Config::from_bytes(&corrupted).is_err(), "{}", index);
+        }
+        // Truncated and extended blobs
+        for len in 0..bytes.len() {
+            assert!(Config::from_bytes(&bytes[..len]).is_err(), "{}", len);
+        }
+        let mut extended: Vec<u8> = bytes.clone();
+        extended.push(0);
+        assert!(Config::from_bytes(&extended).is_err());
+        assert!(Config::from_bytes(&[]).unwrap_err().is_invalid_binary());
+    }
+}
@@ -0,0 +1,238 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::errors::{DomainChangerError, DomainChangerResult};
+use crate::types::{Config, Domain, Group, SafetyPolicy, Service};
+use alloc::{format, vec::Vec};
+use url::Url;
+
+/// [`ConfigBuilder`] struct help you to build [`Config`] step by step
+///
+/// Every step is validated, the first error is returned by [`build`]
+///
+/// # Example
+/// ```rust
+/// use domain_changer::types::{Config, Group};
+///
+/// let config: Config = Config::builder()
+///     .mapping("https://youtube.com/", "https://piped.kavin.rocks/")
+///     .mapping("https://youtu.be/", "https://piped.kavin.rocks/")
+///     .mapping("https://youtu.be/", "https://piped.kavin.rocks/")
+///     .group(Group::new("youtube", &["video"], &["youtube.com", "youtu.be"]))
+///     .dedupe()
+///     .build()
+///     .unwrap();
+/// assert_eq!(config.domains.len(), 2);
+///
+/// assert!(Config::builder()
+///     .mapping("https://youtube.com/", "piped.kavin.rocks")
+///     .build()
+///     .unwrap_err()
+///     .is_invalid_new_domain());
+/// ```
+///
+/// [`build`]: method@ConfigBuilder::build
+#[derive(Debug)]
+pub struct ConfigBuilder {
+    config: Config,
+    error: Option<DomainChangerError>,
+    policies: Vec<SafetyPolicy>,
+}
+
+impl ConfigBuilder {
+    /// Create new empty [`ConfigBuilder`] instance
+    pub fn new() -> Self {
+        Self::from(Config::new(Vec::new()))
+    }
+
+    /// Add the `domain`, see [`Config.insert`]
+    ///
+    /// # Note
+    /// Unlike [`Config.insert`], the domain with an existing [`old`] host is added
+    /// after the existing one, use [`replace`] to replace the target
+    ///
+    /// [`Config.insert`]: method@Config::insert
+    /// [`old`]: Domain#structfield.old
+    /// [`replace`]: method@ConfigBuilder::replace
+    pub fn domain(self, domain: Domain) -> Self {
+        self.step(|config| {
+            domain.validate()?;
+            config.domains.push(domain);
+            Ok(())
+        })
+    }
+
+    /// Add the `old` and `new` domains, see [`ConfigBuilder.domain`]
+    ///
+    /// [`ConfigBuilder.domain`]: method@ConfigBuilder::domain
+    pub fn mapping(self, old: &str, new: &str) -> Self {
+        match Domain::try_from((old, new)) {
+            Ok(domain) => self.domain(domain),
+            Err(err) => self.step(|_| Err(err)),
+        }
+    }
+
+    /// Add the `group`, the group name should be unique
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, Group};
+    ///
+    /// assert!(Config::builder()
+    ///     .group(Group::new("youtube", &[], &[]))
+    ///     .group(Group::new("youtube", &[], &[]))
+    ///     .build()
+    ///     .unwrap_err()
+    ///     .is_duplicate_group());
+    /// ```
+    pub fn group(self, group: Group) -> Self {
+        self.step(|config| {
+            if config.get_group(&group.name).is_some() {
+                return Err(DomainChangerError::DuplicateGroup(format!(
+                    "'{}', there is already a group with this name",
+                    group.name
+                )));
+            }
+            config.groups.push(group);
+            Ok(())
+        })
+    }
+
+    /// Add the `service`, see [`Config.add_service`]
+    ///
+    /// [`Config.add_service`]: method@Config::add_service
+    pub fn service(self, service: Service) -> Self {
+        self.step(|config| config.add_service(service))
+    }
+
+    /// Replace the target of the domain with the `old_host`, see [`Config.replace_target`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Config;
+    ///
+    /// let config: Config = Config::builder()
+    ///     .mapping("https://twitter.com/", "https://nitter.net/")
+    ///     .replace("twitter.com", "https://nitter.it/")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(config.new_hosts(), vec!["nitter.it"]);
+    /// ```
+    ///
+    /// [`Config.replace_target`]: method@Config::replace_target
+    pub fn replace(self, old_host: &str, new: &str) -> Self {
+        self.step(|config| {
+            let new: Url = Url::parse(new).map_err(|_| {
+                DomainChangerError::InvalidNewDomain(format!("'{}', is invalid new domain", new))
+            })?;
+            config.replace_target(old_host, new).map(|_| ())
+        })
+    }
+
+    /// Remove all domains with the `old_host`, see [`Config.remove_by_old`]
+    ///
+    /// [`Config.remove_by_old`]: method@Config::remove_by_old
+    pub fn remove(self, old_host: &str) -> Self {
+        self.step(|config| {
+            config.remove_by_old(old_host);
+            Ok(())
+        })
+    }
+
+    /// Remove the duplicate domains, see [`Config.dedupe`]
+    ///
+    /// [`Config.dedupe`]: method@Config::dedupe
+    pub fn dedupe(self) -> Self {
+        self.step(|config| {
+            config.dedupe();
+            Ok(())
+        })
+    }
+
+    /// Validate the [`new`] domains with the `policy` when the config is built, the domains
+    /// added after this step are validated too, see [`Config.validate`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, SafetyPolicy};
+    ///
+    /// assert!(Config::builder()
+    ///     .validate(&SafetyPolicy::default())
+    ///     .mapping("https://twitter.com/", "http://192.168.1.1/")
+    ///     .build()
+    ///     .unwrap_err()
+    ///     .is_unsafe_target());
+    /// ```
+    ///
+    /// [`new`]: crate::types::Domain#structfield.new
+    /// [`Config.validate`]: method@Config::validate
+    pub fn validate(mut self, policy: &SafetyPolicy) -> Self {
+        self.policies.push(policy.clone());
+        self
+    }
+
+    /// Returns the [`Config`] or the first error of the steps, then the first violation
+    /// of the policies, see [`ConfigBuilder.validate`]
+    ///
+    /// [`ConfigBuilder.validate`]: method@ConfigBuilder::validate
+    pub fn build(self) -> DomainChangerResult<Config> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        for policy in &self.policies {
+            self.config.validate(policy)?;
+        }
+        Ok(self.config)
+    }
+
+    /// Run the step if there is no error
+    fn step<F>(mut self, step: F) -> Self
+    where
+        F: FnOnce(&mut Config) -> DomainChangerResult<()>,
+    {
+        if self.error.is_none() {
+            self.error = step(&mut self.config).err();
+        }
+        self
+    }
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Config> for ConfigBuilder {
+    /// Create [`ConfigBuilder`] from existing [`Config`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, ConfigBuilder};
+    ///
+    /// let config: Config = ConfigBuilder::from(Config::default())
+    ///     .remove("reddit.com")
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(config.get_by_old("reddit.com").is_none());
+    /// ```
+    fn from(config: Config) -> Self {
+        Self {
+            config,
+            error: None,
+            policies: Vec::new(),
+        }
+    }
+}
@@ -13,17 +13,27 @@
 // You should have received a copy of the GNU Affero General Public License along
 // with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use super::errors::{DomainChangerError, DomainChangerResult};
 #[cfg(feature = "json")]
 use super::ToFromJson;
-use crate::types::Domain;
-#[cfg(feature = "json")]
-use serde::{Deserialize, Serialize};
+use crate::canonicalize;
+use crate::host::{host_key, same_host};
+use crate::types::{Canonicalization, ConfigBuilder, Domain, Group, Service};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::String,
+    vec,
+    vec::Vec,
+};
+use url::Url;
 
 /// [`Config`] struct help you to manage domains with [`Domain`] struct
-#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Config {
     pub domains: Vec<Domain>,
+    /// groups of the domains, see [`Group`]
+    pub groups: Vec<Group>,
 }
 
 impl Config {
@@ -44,7 +54,442 @@ impl Config {
     /// ```
     ///
     pub fn new(domains: Vec<Domain>) -> Self {
-        Self { domains }
+        Self {
+            domains,
+            groups: Vec::new(),
+        }
+    }
+
+    /// Create new [`Config`] instance with groups
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, Domain, Group};
+    ///
+    /// let my_config = Config::with_groups(
+    ///     vec![
+    ///         Domain::try_from(("https://twitter.com/", "https://nitter.net/")).unwrap(),
+    ///         Domain::try_from(("https://reddit.com/", "https://libredd.it/")).unwrap()
+    ///     ],
+    ///     vec![
+    ///         Group::new("twitter", &["social"], &["twitter.com"]),
+    ///         Group::new("reddit", &["social", "news"], &["reddit.com"])
+    ///     ],
+    /// );
+    ///
+    /// assert_eq!(my_config.groups.len(), 2);
+    /// assert_eq!(my_config.groups[1].name, "reddit");
+    /// ```
+    pub fn with_groups(domains: Vec<Domain>, groups: Vec<Group>) -> Self {
+        Self { domains, groups }
+    }
+
+    /// Returns new [`ConfigBuilder`] to build [`Config`] step by step
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Config;
+    ///
+    /// let config: Config = Config::builder()
+    ///     .mapping("https://twitter.com", "https://nitter.net")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(config.old_hosts(), vec!["twitter.com"]);
+    /// ```
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::new()
+    }
+
+    /// Create new [`Config`] instance from services, see [`Config.add_service`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, Service};
+    /// use url::Url;
+    ///
+    /// let my_config = Config::from_services(vec![
+    ///     Service::new(
+    ///         "youtube",
+    ///         &["video"],
+    ///         &["youtube.com", "www.youtube.com", "youtu.be"],
+    ///         vec![Url::parse("https://piped.kavin.rocks").unwrap()],
+    ///     ),
+    ///     Service::new(
+    ///         "twitter",
+    ///         &["social"],
+    ///         &["twitter.com"],
+    ///         vec![Url::parse("https://nitter.net").unwrap()],
+    ///     ),
+    /// ]).unwrap();
+    ///
+    /// assert_eq!(my_config.domains.len(), 4);
+    /// assert_eq!(my_config.groups.len(), 2);
+    /// ```
+    ///
+    /// [`Config.add_service`]: method@Config::add_service
+    pub fn from_services(services: Vec<Service>) -> DomainChangerResult<Self> {
+        let mut config: Self = Self::new(Vec::new());
+        for service in services {
+            config.add_service(service)?;
+        }
+        Ok(config)
+    }
+
+    /// Compile the `service` to [`Domain`]s and add them with the [`Group`] of the service
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, Service};
+    /// use url::Url;
+    ///
+    /// let mut config: Config = Config::new(Vec::new());
+    /// config.add_service(Service::new(
+    ///     "reddit",
+    ///     &["social", "news"],
+    ///     &["reddit.com", "old.reddit.com"],
+    ///     vec![Url::parse("https://libredd.it").unwrap()],
+    /// )).unwrap();
+    ///
+    /// assert_eq!(config.old_hosts(), vec!["reddit.com", "old.reddit.com"]);
+    /// assert_eq!(config.get_group("reddit").unwrap().tags, vec!["social", "news"]);
+    /// assert!(config
+    ///     .add_service(Service::new("reddit", &[], &["redd.it"], Vec::new()))
+    ///     .unwrap_err()
+    ///     .is_duplicate_group());
+    /// ```
+    ///
+    /// # Errors
+    /// - Returns [`DuplicateGroup`] error if there is already a group with the service name
+    /// - Returns the errors of [`Service::domains`] if a compiled domain is invalid
+    ///
+    /// [`DuplicateGroup`]: crate::types::errors::DomainChangerError::DuplicateGroup
+    pub fn add_service(&mut self, service: Service) -> DomainChangerResult<()> {
+        if self.get_group(&service.name).is_some() {
+            return Err(DomainChangerError::DuplicateGroup(format!(
+                "'{}', there is already a group with this name",
+                service.name
+            )));
+        }
+        let group: Group = service.group()?;
+        self.domains.extend(service.domains()?);
+        self.groups.push(group);
+        Ok(())
+    }
+
+    /// Validate the `domain` and insert it, the domains with the same [`old`] host are replaced
+    /// by it and returned, it takes the position of the first one of them
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, Domain};
+    ///
+    /// let mut config: Config = Config::default();
+    /// config.domains.push(Domain::try_from(("https://twitter.com/", "https://nitter.it/")).unwrap());
+    /// let old_domains: Vec<Domain> = config
+    ///     .insert(Domain::try_from(("https://twitter.com/", "https://nitter.fdn.fr/")).unwrap())
+    ///     .unwrap();
+    /// assert_eq!(old_domains.len(), 2);
+    /// assert_eq!(old_domains[0].new.as_str(), "https://nitter.net/");
+    /// assert_eq!(config.get_by_old("twitter.com").unwrap().new.as_str(), "https://nitter.fdn.fr/");
+    /// assert_eq!(config.old_hosts().iter().filter(|host| **host == "twitter.com").count(), 1);
+    /// assert!(config
+    ///     .insert(Domain::try_from(("https://vimeo.com/", "https://vimeo.com/")).unwrap())
+    ///     .is_err());
+    /// ```
+    ///
+    /// # Note
+    /// The [`new`] domain is not validated with a [`SafetyPolicy`], validate the config
+    /// after inserting to an untrusted domain, see [`Config::validate`]
+    ///
+    /// [`old`]: Domain#structfield.old
+    /// [`new`]: Domain#structfield.new
+    /// [`SafetyPolicy`]: crate::types::SafetyPolicy
+    pub fn insert(&mut self, domain: Domain) -> DomainChangerResult<Vec<Domain>> {
+        domain.validate()?;
+        let key: Option<String> = domain.old.host_str().map(host_key);
+        let mut domain: Option<Domain> = Some(domain);
+        let mut replaced: Vec<Domain> = Vec::new();
+        for config_domain in core::mem::take(&mut self.domains) {
+            if key.is_some() && config_domain.old.host_str().map(host_key) == key {
+                replaced.push(config_domain);
+                self.domains.extend(domain.take());
+            } else {
+                self.domains.push(config_domain);
+            }
+        }
+        self.domains.extend(domain);
+        Ok(replaced)
+    }
+
+    /// Remove all domains with the `old_host` and returns them, the host will be
+    /// removed from its group too
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Config;
+    ///
+    /// let mut config: Config = Config::default();
+    /// assert_eq!(config.remove_by_old("t.co").len(), 1);
+    /// assert!(config.get_by_old("t.co").is_none());
+    /// assert_eq!(config.get_group("twitter").unwrap().hosts, vec!["twitter.com"]);
+    /// assert!(config.remove_by_old("t.co").is_empty());
+    /// ```
+    pub fn remove_by_old(&mut self, old_host: &str) -> Vec<Domain> {
+        let mut removed: Vec<Domain> = Vec::new();
+        self.domains.retain(|domain| {
+            if domain
+                .old
+                .host_str()
+                .is_some_and(|host| same_host(host, old_host))
+            {
+                removed.push(domain.clone());
+                false
+            } else {
+                true
+            }
+        });
+        self.prune_groups();
+        removed
+    }
+
+    /// Replace the [`new`] domain of all domains with the `old_host`, returns the old targets
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Config;
+    /// use url::Url;
+    ///
+    /// let mut config: Config = Config::default();
+    /// let old_targets: Vec<Url> = config
+    ///     .replace_target("reddit.com", Url::parse("https://teddit.net").unwrap())
+    ///     .unwrap();
+    /// assert_eq!(old_targets[0].as_str(), "https://libredd.it/");
+    /// assert_eq!(config.new_hosts().last(), Some(&"teddit.net"));
+    /// assert!(config
+    ///     .replace_target("vimeo.com", Url::parse("https://teddit.net").unwrap())
+    ///     .unwrap_err()
+    ///     .is_domain_not_found());
+    /// ```
+    ///
+    /// # Note
+    /// The target is not validated with a [`SafetyPolicy`], validate the config after
+    /// replacing to an untrusted target, see [`Config::validate`]
+    ///
+    /// [`new`]: Domain#structfield.new
+    /// [`SafetyPolicy`]: crate::types::SafetyPolicy
+    pub fn replace_target(&mut self, old_host: &str, new: Url) -> DomainChangerResult<Vec<Url>> {
+        let mut domains: Vec<&mut Domain> = self
+            .domains
+            .iter_mut()
+            .filter(|domain| {
+                domain
+                    .old
+                    .host_str()
+                    .is_some_and(|host| same_host(host, old_host))
+            })
+            .collect();
+        let Some(domain) = domains.first() else {
+            return Err(DomainChangerError::DomainNotFound(format!(
+                "'{}', there is no domain with this old host",
+                old_host
+            )));
+        };
+        Domain::new(domain.old.clone(), new.clone()).validate()?;
+        Ok(domains
+            .iter_mut()
+            .map(|domain| core::mem::replace(&mut domain.new, new.clone()))
+            .collect())
+    }
+
+    /// Retains only the domains specified by the predicate, the groups will be updated
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Config;
+    ///
+    /// let mut config: Config = Config::default();
+    /// config.retain(|domain| domain.new.host_str() != Some("piped.kavin.rocks"));
+    /// assert_eq!(config.domains.len(), 3);
+    /// assert!(config.get_group("youtube").unwrap().hosts.is_empty());
+    /// ```
+    pub fn retain<F>(&mut self, predicate: F)
+    where
+        F: FnMut(&Domain) -> bool,
+    {
+        self.domains.retain(predicate);
+        self.prune_groups();
+    }
+
+    /// Remove the duplicate domains and keep the first one, returns the number of removed domains.
+    /// The domains are duplicate if they have the same [`old`] host and the same [`new`] domain
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, Domain};
+    ///
+    /// let mut config: Config = Config::default();
+    /// config.domains.push(Domain::try_from(("https://t.co/", "https://nitter.net/")).unwrap());
+    /// assert_eq!(config.dedupe(), 1);
+    /// assert_eq!(config, Config::default());
+    /// ```
+    ///
+    /// [`old`]: Domain#structfield.old
+    /// [`new`]: Domain#structfield.new
+    pub fn dedupe(&mut self) -> usize {
+        let len: usize = self.domains.len();
+        let mut seen: BTreeSet<(String, String)> = BTreeSet::new();
+        self.domains.retain(|domain| {
+            let old: String = domain
+                .old
+                .host_str()
+                .map_or_else(|| domain.old.as_str().into(), host_key);
+            seen.insert((old, domain.new.as_str().into()))
+        });
+        len - self.domains.len()
+    }
+
+    /// Merge the `other` config over this config, the domains of `other` replace all domains
+    /// with the same [`old`] host (at the position of the first one of them) and the rest of them
+    /// are appended. The groups with the same name are merged (the enabled state of this config
+    /// is kept) and the rest of them are appended
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, Group};
+    ///
+    /// let mut config: Config = Config::default();
+    /// config.disable_group("twitter");
+    /// config.merge(
+    ///     Config::builder()
+    ///         .mapping("https://twitter.com", "https://nitter.it")
+    ///         .mapping("https://x.com", "https://nitter.it")
+    ///         .group(Group::new("twitter", &[], &["x.com"]))
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// assert_eq!(config.get_by_old("twitter.com").unwrap().new.as_str(), "https://nitter.it/");
+    /// assert_eq!(config.old_hosts().last(), Some(&"x.com"));
+    /// assert_eq!(config.old_hosts().iter().filter(|host| **host == "twitter.com").count(), 1);
+    /// assert_eq!(
+    ///     config.get_group("twitter").unwrap().hosts,
+    ///     vec!["t.co", "twitter.com", "x.com"]
+    /// );
+    /// assert!(!config.get_group("twitter").unwrap().enabled);
+    /// ```
+    ///
+    /// [`old`]: Domain#structfield.old
+    pub fn merge(&mut self, other: Config) {
+        let key = |domain: &Domain| {
+            domain
+                .old
+                .host_str()
+                .map_or_else(|| domain.old.as_str().into(), host_key)
+        };
+        // The domains of `other` by their old hosts, in the order of the hosts
+        let mut hosts: Vec<String> = Vec::new();
+        let mut domains: BTreeMap<String, Vec<Domain>> = BTreeMap::new();
+        for domain in other.domains {
+            domains
+                .entry(key(&domain))
+                .or_insert_with_key(|host| {
+                    hosts.push(host.clone());
+                    Vec::new()
+                })
+                .push(domain);
+        }
+        for domain in core::mem::take(&mut self.domains) {
+            match domains.get_mut(&key(&domain)) {
+                // The first domain of the host is replaced and the rest are removed
+                Some(other_domains) => self.domains.append(other_domains),
+                None => self.domains.push(domain),
+            }
+        }
+        for host in hosts {
+            self.domains
+                .extend(domains.remove(&host).unwrap_or_default());
+        }
+        for group in other.groups {
+            match self
+                .groups
+                .iter_mut()
+                .find(|config_group| config_group.name == group.name)
+            {
+                Some(config_group) => {
+                    for tag in group.tags {
+                        if !config_group.tags.contains(&tag) {
+                            config_group.tags.push(tag);
+                        }
+                    }
+                    for host in group.hosts {
+                        if !config_group.contain(&host) {
+                            config_group.hosts.push(host);
+                        }
+                    }
+                    for frontend in group.frontends {
+                        if !config_group.frontends.contains(&frontend) {
+                            config_group.frontends.push(frontend);
+                        }
+                    }
+                }
+                None => self.groups.push(group),
+            }
+        }
+    }
+
+    /// Returns the services of the config, one service for every [`Group`]
+    ///
+    /// The frontends of the service are the [`new`] domains of the group domains,
+    /// then the [`frontends`] of the group
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, Service};
+    ///
+    /// let config: Config = Config::default();
+    /// let services: Vec<Service> = config.services();
+    /// assert_eq!(services.len(), 3);
+    /// assert_eq!(services[0].name, "youtube");
+    /// assert_eq!(services[0].hosts, vec!["youtube.com", "www.youtube.com", "youtu.be"]);
+    /// assert_eq!(services[0].target().unwrap().as_str(), "https://piped.kavin.rocks/");
+    /// ```
+    ///
+    /// [`new`]: Domain#structfield.new
+    /// [`frontends`]: Group#structfield.frontends
+    pub fn services(&self) -> Vec<Service> {
+        self.groups
+            .iter()
+            .map(|group| self.service_from_group(group))
+            .collect()
+    }
+
+    /// Returns the [`Service`] of the `host` if any, the host can be
+    /// one of the service hosts or one of its frontends
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Config;
+    ///
+    /// let config: Config = Config::default();
+    /// assert_eq!(config.service_of("youtu.be").unwrap().name, "youtube");
+    /// assert_eq!(config.service_of("nitter.net").unwrap().name, "twitter");
+    /// assert!(config.service_of("google.com").is_none());
+    /// ```
+    pub fn service_of(&self, host: &str) -> Option<Service> {
+        self.groups
+            .iter()
+            .find(|group| group.contain(host) || group.is_frontend(host))
+            .or_else(|| {
+                self.domains
+                    .iter()
+                    .filter(|domain| {
+                        domain
+                            .new
+                            .host_str()
+                            .is_some_and(|new| same_host(new, host))
+                    })
+                    .find_map(|domain| self.group_of(domain))
+            })
+            .map(|group| self.service_from_group(group))
     }
 
     /// Returns all old host name of domains
@@ -111,7 +556,7 @@ impl Config {
     pub fn get_by_old(&self, old_host: &str) -> Option<&Domain> {
         self.domains.iter().find(|domain| {
             if let Some(host) = domain.old.host_str() {
-                host == old_host
+                same_host(host, old_host)
             } else {
                 false
             }
@@ -136,14 +581,225 @@ impl Config {
     /// [`old`]: Domain#structfield.old
     /// [`Domain.contain`]: method@Domain::contain
     pub fn contain(&self, word: &str, just_old: bool) -> Option<&Domain> {
-        self.domains
+        let url: Url = canonicalize(word, &Canonicalization::default())?;
+        self.active_domains()
+            .find(|domain| domain.matches(&url, just_old))
+    }
+
+    /// Returns reference [`Group`] from [`groups`] by `name` if any
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Config;
+    ///
+    /// let config: Config = Config::default();
+    /// assert_eq!(config.get_group("youtube").unwrap().hosts.len(), 3);
+    /// assert_eq!(config.get_group("vimeo"), None);
+    /// ```
+    ///
+    /// [`groups`]: Config#structfield.groups
+    pub fn get_group(&self, name: &str) -> Option<&Group> {
+        self.groups.iter().find(|group| group.name == name)
+    }
+
+    /// Returns the [`Group`] of the `domain` if any (by [`old`] host)
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Config;
+    ///
+    /// let config: Config = Config::default();
+    /// let domain = config.get_by_old("t.co").unwrap();
+    /// assert_eq!(config.group_of(domain).unwrap().name, "twitter");
+    /// ```
+    ///
+    /// [`old`]: Domain#structfield.old
+    pub fn group_of(&self, domain: &Domain) -> Option<&Group> {
+        let host = domain.old.host_str()?;
+        self.groups.iter().find(|group| group.contain(host))
+    }
+
+    /// Returns all groups that have the `tag`
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Config;
+    ///
+    /// let config: Config = Config::default();
+    /// let names: Vec<&str> = config
+    ///     .groups_with_tag("social")
+    ///     .iter()
+    ///     .map(|group| group.name.as_str())
+    ///     .collect();
+    /// assert_eq!(names, vec!["twitter", "reddit"]);
+    /// ```
+    pub fn groups_with_tag(&self, tag: &str) -> Vec<&Group> {
+        self.groups
             .iter()
-            .find(|domain| domain.contain(word, just_old).is_some())
+            .filter(|group| group.has_tag(tag))
+            .collect()
+    }
+
+    /// Enable the group by `name`, returns [`false`] if there is no group with this name
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Config;
+    ///
+    /// let mut config: Config = Config::default();
+    /// config.disable_group("reddit");
+    /// assert!(config.contain("reddit.com", true).is_none());
+    /// assert!(config.enable_group("reddit"));
+    /// assert!(config.contain("reddit.com", true).is_some());
+    /// assert!(!config.enable_group("vimeo"));
+    /// ```
+    pub fn enable_group(&mut self, name: &str) -> bool {
+        self.set_group_enabled(name, true)
+    }
+
+    /// Disable the group by `name`, returns [`false`] if there is no group with this name
+    ///
+    /// The domains of disabled group will be ignored by [`Config.contain`], [`parse_string`]
+    /// and [`extract_old_domains`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::parse_string;
+    /// use domain_changer::types::Config;
+    ///
+    /// let mut config: Config = Config::default();
+    /// assert!(config.disable_group("reddit"));
+    /// assert_eq!(
+    ///     parse_string(&config, "https://reddit.com/r/rust".to_owned()),
+    ///     "https://reddit.com/r/rust".to_owned()
+    /// );
+    /// ```
+    ///
+    /// [`Config.contain`]: method@Config::contain
+    /// [`parse_string`]: crate::parse_string
+    /// [`extract_old_domains`]: crate::extract_old_domains
+    pub fn disable_group(&mut self, name: &str) -> bool {
+        self.set_group_enabled(name, false)
+    }
+
+    /// Enable or disable all groups that have the `tag`, returns the number of changed groups
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Config;
+    ///
+    /// let mut config: Config = Config::default();
+    /// assert_eq!(config.set_tag_enabled("social", false), 2);
+    /// assert!(config.contain("twitter.com", true).is_none());
+    /// assert!(config.contain("youtube.com", true).is_some());
+    /// ```
+    pub fn set_tag_enabled(&mut self, tag: &str, enabled: bool) -> usize {
+        self.groups
+            .iter_mut()
+            .filter(|group| group.has_tag(tag))
+            .map(|group| group.enabled = enabled)
+            .count()
+    }
+
+    /// Returns [`true`] if the `domain` will be changed, it is not in disabled group
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Config;
+    ///
+    /// let mut config: Config = Config::default();
+    /// config.disable_group("youtube");
+    /// assert!(!config.is_enabled(&config.domains[0]));
+    /// assert!(config.is_enabled(config.get_by_old("twitter.com").unwrap()));
+    /// ```
+    pub fn is_enabled(&self, domain: &Domain) -> bool {
+        self.group_of(domain).is_none_or(|group| group.enabled)
+    }
+
+    /// Returns iterator of the domains that not in disabled group
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Config;
+    ///
+    /// let mut config: Config = Config::default();
+    /// config.disable_group("youtube");
+    /// assert_eq!(config.active_domains().count(), config.domains.len() - 3);
+    /// ```
+    pub fn active_domains(&self) -> impl Iterator<Item = &Domain> {
+        // The disabled hosts are computed once, the host is disabled if its first group is disabled
+        let mut disabled: BTreeMap<String, bool> = BTreeMap::new();
+        if self.groups.iter().any(|group| !group.enabled) {
+            for group in &self.groups {
+                for host in &group.hosts {
+                    disabled.entry(host_key(host)).or_insert(!group.enabled);
+                }
+            }
+            disabled.retain(|_, disabled| *disabled);
+        }
+        self.domains.iter().filter(move |domain| {
+            disabled.is_empty()
+                || domain
+                    .old
+                    .host_str()
+                    .is_none_or(|host| !disabled.contains_key(&host_key(host)))
+        })
+    }
+
+    /// Remove the hosts of the groups that are not [`old`] host of any domain
+    ///
+    /// [`old`]: Domain#structfield.old
+    fn prune_groups(&mut self) {
+        let old_hosts: Vec<String> = self.old_hosts().into_iter().map(String::from).collect();
+        for group in self.groups.iter_mut() {
+            group
+                .hosts
+                .retain(|host| old_hosts.iter().any(|old_host| same_host(old_host, host)));
+        }
+    }
+
+    fn service_from_group(&self, group: &Group) -> Service {
+        let mut frontends: Vec<Url> = Vec::new();
+        for frontend in self
+            .domains
+            .iter()
+            .filter(|domain| {
+                domain
+                    .old
+                    .host_str()
+                    .is_some_and(|host| group.contain(host))
+            })
+            .map(|domain| &domain.new)
+            .chain(&group.frontends)
+        {
+            if !frontends.contains(frontend) {
+                frontends.push(frontend.clone());
+            }
+        }
+        Service {
+            name: group.name.clone(),
+            tags: group.tags.clone(),
+            hosts: group.hosts.clone(),
+            frontends,
+        }
+    }
+
+    fn set_group_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        self.groups
+            .iter_mut()
+            .find(|group| group.name == name)
+            .map(|group| group.enabled = enabled)
+            .is_some()
     }
 }
 
 /// Implementation [`ToFromJson`] to [`Config`]
 ///
+/// The config can be deserialized from `{"version": 2, "domains": [..], "groups": [..], "services": [..]}`,
+/// the domains can be a map of `"old": "new"`. The older versions of the config format are migrated on load,
+/// see [`migration`]. It is serialized to `{"version": 2, "domains": [..], "groups": [..]}`,
+/// see [`Domain`] serialization, or to `{"version": 2, "services": [..]}` if it is compiled from its services
+///
 /// # Example
 /// ```rust
 /// use domain_changer::types::{Config, Domain, ToFromJson};
@@ -151,16 +807,18 @@ impl Config {
 /// let config: Config = Config::new(vec![Domain::try_from(("https://twitter.com/", "https://nitter.net/")).unwrap()]);
 /// assert_eq!(
 ///     config.to_json().unwrap(),
-///     "{\"domains\":[{\"old\":\"https://twitter.com/\",\"new\":\"https://nitter.net/\"}]}".to_string()
+///     "{\"version\":2,\"domains\":[{\"old\":\"https://twitter.com/\",\"new\":\"https://nitter.net/\"}]}".to_string()
 /// );
 ///
 /// assert_eq!(
 ///     Config::from_json("{\"domains\":[{\"old\":\"https://twitter.com/\",\"new\":\"https://nitter.net/\"}]}").unwrap(),
 ///     config
-/// )
-///
-///
+/// );
+/// assert_eq!(Config::from_json("{\"domains\":[\"twitter.com -> nitter.net\"]}").unwrap(), config);
+/// assert_eq!(Config::from_json("{\"twitter.com\":\"nitter.net\"}").unwrap(), config);
 /// ```
+///
+/// [`migration`]: crate::types::migration
 #[cfg(feature = "json")]
 impl ToFromJson<'_> for Config {}
 
@@ -171,16 +829,131 @@ impl Default for Config {
     /// [libredd](https://libredd.it/)
     ///
     fn default() -> Self {
-        Self::new(vec![
-            // Youtube domains
-            Domain::try_from(("https://youtube.com/", "https://piped.kavin.rocks/")).unwrap(),
-            Domain::try_from(("https://www.youtube.com/", "https://piped.kavin.rocks/")).unwrap(),
-            Domain::try_from(("https://youtu.be/", "https://piped.kavin.rocks/")).unwrap(),
-            // Twitter domains
-            Domain::try_from(("https://t.co/", "https://nitter.net/")).unwrap(),
-            Domain::try_from(("https://twitter.com/", "https://nitter.net/")).unwrap(),
-            // Reddit domains
-            Domain::try_from(("https://reddit.com/", "https://libredd.it/")).unwrap(),
+        Self::from_services(vec![
+            Service::new(
+                "youtube",
+                &["video"],
+                &["youtube.com", "www.youtube.com", "youtu.be"],
+                vec![Url::parse("https://piped.kavin.rocks/").unwrap()],
+            ),
+            Service::new(
+                "twitter",
+                &["social"],
+                &["t.co", "twitter.com"],
+                vec![Url::parse("https://nitter.net/").unwrap()],
+            ),
+            Service::new(
+                "reddit",
+                &["social", "news"],
+                &["reddit.com"],
+                vec![Url::parse("https://libredd.it/").unwrap()],
+            ),
         ])
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{Config, Domain, Group, Service};
+    use alloc::{vec, vec::Vec};
+    use url::Url;
+
+    #[test]
+    fn merge_duplicates_test() {
+        let domain = |old: &str, new: &str| Domain::lenient(old, new).unwrap();
+        let mut config: Config = Config::new(vec![
+            domain("twitter.com", "nitter.net"),
+            domain("youtube.com", "piped.kavin.rocks"),
+            domain("TWITTER.com", "nitter.it"),
+            domain("twitter.com", "nitter.net"),
+        ]);
+        config.merge(Config::new(vec![
+            domain("reddit.com", "libredd.it"),
+            domain("twitter.com", "nitter.fdn.fr"),
+            domain("twitter.com", "nitter.poast.org"),
+        ]));
+        assert_eq!(
+            config.domains,
+            vec![
+                domain("twitter.com", "nitter.fdn.fr"),
+                domain("twitter.com", "nitter.poast.org"),
+                domain("youtube.com", "piped.kavin.rocks"),
+                domain("reddit.com", "libredd.it"),
+            ]
+        );
+    }
+
+    #[test]
+    fn service_frontends_test() {
+        let frontends: Vec<Url> = ["https://piped.kavin.rocks/", "https://yewtu.be/"]
+            .map(|frontend| Url::parse(frontend).unwrap())
+            .to_vec();
+        let service: Service = Service::new(
+            "youtube",
+            &["video"],
+            &["youtube.com", "youtu.be"],
+            frontends.clone(),
+        );
+        let config: Config = Config::from_services(vec![service.clone()]).unwrap();
+        // The alternative frontends are not rules
+        assert_eq!(config.old_hosts(), vec!["youtube.com", "youtu.be"]);
+        assert_eq!(config.get_group("youtube").unwrap().frontends, frontends);
+        assert_eq!(config.services(), vec![service]);
+        assert_eq!(config.service_of("yewtu.be").unwrap().name, "youtube");
+    }
+
+    #[test]
+    fn active_domains_test() {
+        let domain = |old: &str, new: &str| Domain::lenient(old, new).unwrap();
+        let mut config: Config = Config::new(vec![
+            domain("twitter.com", "nitter.net"),
+            domain("bücher.de", "bücher.example"),
+            domain("t.co", "nitter.net"),
+            domain("vimeo.com", "vimeo.example"),
+        ]);
+        let mut disabled: Group = Group::new("disabled", &[], &["TWITTER.com", "xn--bcher-kva.de"]);
+        disabled.enabled = false;
+        // The first group of the host decides, `t.co` is in an enabled group first
+        config.groups = vec![
+            Group::new("enabled", &[], &["t.co"]),
+            disabled,
+            Group::new("twitter", &[], &["twitter.com", "t.co"]),
+        ];
+        let active: Vec<&str> = config
+            .active_domains()
+            .map(|domain| domain.old.host_str().unwrap())
+            .collect();
+        assert_eq!(active, ["t.co", "vimeo.com"]);
+        assert!(config
+            .domains
+            .iter()
+            .all(|domain| config.is_enabled(domain)
+                == active.contains(&domain.old.host_str().unwrap())));
+    }
+
+    #[test]
+    fn invalid_service_test() {
+        let service = |frontend: &str| {
+            Service::new(
+                "twitter",
+                &[],
+                &["twitter.com", "t.co"],
+                vec![Url::parse(frontend).unwrap()],
+            )
+        };
+        // The frontend is one of the hosts of the service
+        let same_host: Service = service("https://T.co/");
+        assert!(same_host.domains().unwrap_err().is_invalid_new_domain());
+        assert!(Config::from_services(vec![same_host.clone()])
+            .unwrap_err()
+            .is_invalid_new_domain());
+        let mut config: Config = Config::new(Vec::new());
+        assert!(config.add_service(same_host).is_err());
+        // The config is not changed by the invalid service
+        assert_eq!(config, Config::new(Vec::new()));
+        assert!(Config::from_services(vec![service("file:///x")])
+            .unwrap_err()
+            .is_invalid_new_domain());
     }
 }
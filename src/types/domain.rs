@@ -16,8 +16,12 @@
 use super::errors::{DomainChangerError, DomainChangerResult};
 #[cfg(feature = "json")]
 use super::ToFromJson;
+use crate::types::Canonicalization;
+use crate::{canonicalize, host::same_host};
+use alloc::{borrow::ToOwned, format};
+use core::str::FromStr;
 #[cfg(feature = "json")]
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use url::Url;
 
 /// [`Domain`] struct help you to put [`old`] and [`new`] domain
@@ -26,7 +30,7 @@ use url::Url;
 ///
 /// [`new`]: Domain#structfield.new
 /// [`old`]: Domain#structfield.old
-#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json", derive(Serialize))]
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Domain {
     /// old domain to change it
@@ -54,6 +58,31 @@ impl Domain {
         Self { old, new }
     }
 
+    /// Create [`Domain`] instance like [`Domain::try_from`], but the domains
+    /// can be bare hosts without a scheme, `https://` will be used
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Domain;
+    ///
+    /// assert_eq!(
+    ///     Domain::lenient("twitter.com", "nitter.net").unwrap(),
+    ///     Domain::try_from(("https://twitter.com/", "https://nitter.net/")).unwrap()
+    /// );
+    /// assert!(Domain::lenient("twitter.com", "http://nitter.net").is_ok());
+    /// assert!(Domain::lenient("twitter .com", "nitter.net").unwrap_err().is_invalid_old_domain());
+    /// ```
+    pub fn lenient(old: &str, new: &str) -> DomainChangerResult<Self> {
+        Ok(Self {
+            old: lenient_url(old).ok_or_else(|| {
+                DomainChangerError::InvalidOldDomain(format!("'{}', is invalid old domain", old))
+            })?,
+            new: lenient_url(new).ok_or_else(|| {
+                DomainChangerError::InvalidNewDomain(format!("'{}', is invalid new domain", new))
+            })?,
+        })
+    }
+
     /// Returns the [`Url`] if `word` is url and is in the domain
     /// check [`old`] only if `just_old` is [`true`]
     ///
@@ -74,30 +103,69 @@ impl Domain {
     ///
     /// [`old`]: Domain#structfield.old
     pub fn contain(&self, word: &str, just_old: bool) -> Option<Url> {
-        let host_with_https: String = "https://".to_owned() + word;
-        if let Ok(url) = Url::parse(
-            if word.starts_with("https://") || word.starts_with("http://") {
-                word
-            } else {
-                &host_with_https
-            },
-        ) {
-            if let Some(str_host) = url.host_str() {
-                if self.old.has_host() && (self.old.host_str().unwrap() == str_host)
-                    || !just_old
-                        && self.new.has_host()
-                        && (self.new.host_str().unwrap() == str_host)
-                {
-                    return Some(url);
-                }
-            }
+        canonicalize(word, &Canonicalization::default()).filter(|url| self.matches(url, just_old))
+    }
+
+    /// Returns [`true`] if the host of the canonicalized `url` is the [`old`] host,
+    /// or the [`new`] host if `just_old` is [`false`]
+    ///
+    /// [`old`]: Domain#structfield.old
+    /// [`new`]: Domain#structfield.new
+    pub(crate) fn matches(&self, url: &Url, just_old: bool) -> bool {
+        url.host_str().is_some_and(|host| {
+            self.old.host_str().is_some_and(|old| same_host(old, host))
+                || !just_old && self.new.host_str().is_some_and(|new| same_host(new, host))
+        })
+    }
+
+    /// Validate the domain, the [`old`] and [`new`] domains should have a host,
+    /// and they should not be the same host
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Domain;
+    ///
+    /// assert!(Domain::try_from(("https://youtube.com", "https://piped.kavin.rocks")).unwrap().validate().is_ok());
+    /// assert!(Domain::try_from(("https://youtube.com", "https://youtube.com/x")).unwrap().validate().is_err());
+    /// assert!(Domain::try_from(("file:///tmp", "https://piped.kavin.rocks")).unwrap().validate().is_err());
+    /// ```
+    ///
+    /// [`old`]: Domain#structfield.old
+    /// [`new`]: Domain#structfield.new
+    pub fn validate(&self) -> DomainChangerResult<()> {
+        let old_host: &str = self.old.host_str().ok_or_else(|| {
+            DomainChangerError::InvalidOldDomain(format!("'{}', old domain has no host", self.old))
+        })?;
+        let new_host: &str = self.new.host_str().ok_or_else(|| {
+            DomainChangerError::InvalidNewDomain(format!("'{}', new domain has no host", self.new))
+        })?;
+        if same_host(old_host, new_host) {
+            return Err(DomainChangerError::InvalidNewDomain(format!(
+                "'{}', new domain is the same as the old domain",
+                self.new
+            )));
         }
-        None
+        Ok(())
+    }
+}
+
+/// Parse the `url`, `https://` will be used if the `url` has no scheme
+pub(crate) fn lenient_url(url: &str) -> Option<Url> {
+    let url: &str = url.trim();
+    if url.contains("://") {
+        Url::parse(url).ok()
+    } else {
+        Url::parse(&("https://".to_owned() + url)).ok()
     }
+    .filter(|url| url.has_host())
 }
 
 /// Implementation [`ToFromJson`] to [`Domain`]
 ///
+/// The domain can be deserialized from `{"old": .., "new": ..}` or from `"old -> new"`,
+/// and the domains can be bare hosts, see [`Domain::lenient`].
+/// It is serialized to `{"old": .., "new": ..}` with full urls
+///
 /// # Example
 /// ```rust
 /// use domain_changer::types::{Domain, ToFromJson};
@@ -105,10 +173,12 @@ impl Domain {
 /// let domain: Domain = Domain::try_from(("https://twitter.com/", "https://nitter.net/")).unwrap();
 /// assert_eq!(domain.to_json().unwrap(), "{\"old\":\"https://twitter.com/\",\"new\":\"https://nitter.net/\"}");
 /// assert_eq!(Domain::from_json("{\"old\":\"https://twitter.com/\",\"new\":\"https://nitter.net/\"}").unwrap(), domain);
-/// assert!(Domain::from_json("{\"old\":\"twitter.com/\",\"new\":\"nitter.net/\"}").is_err())
-///
-///
+/// assert_eq!(Domain::from_json("{\"old\":\"twitter.com\",\"new\":\"nitter.net\"}").unwrap(), domain);
+/// assert_eq!(Domain::from_json("\"twitter.com -> nitter.net\"").unwrap(), domain);
+/// assert!(Domain::from_json("{\"old\":\"twitter.com\"}").is_err());
 /// ```
+///
+/// [`Domain::lenient`]: method@Domain::lenient
 #[cfg(feature = "json")]
 impl ToFromJson<'_> for Domain {}
 
@@ -147,6 +217,33 @@ impl TryFrom<(&str, &str)> for Domain {
     }
 }
 
+impl FromStr for Domain {
+    type Err = DomainChangerError;
+
+    /// Parse [`Domain`] from `"old -> new"`, see [`Domain::lenient`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Domain;
+    ///
+    /// let domain: Domain = "youtube.com -> https://piped.kavin.rocks".parse().unwrap();
+    /// assert_eq!(domain.old.as_str(), "https://youtube.com/");
+    /// assert_eq!(domain.new.as_str(), "https://piped.kavin.rocks/");
+    /// assert!("youtube.com".parse::<Domain>().unwrap_err().is_invalid_new_domain());
+    /// ```
+    ///
+    /// [`Domain::lenient`]: method@Domain::lenient
+    fn from_str(domains: &str) -> DomainChangerResult<Self> {
+        match domains.split_once("->") {
+            Some((old, new)) => Self::lenient(old, new),
+            None => Err(DomainChangerError::InvalidNewDomain(format!(
+                "'{}', there is no new domain, expected 'old -> new'",
+                domains
+            ))),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::types::{errors::DomainChangerResult, Domain};
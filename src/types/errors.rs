@@ -13,6 +13,9 @@
 // You should have received a copy of the GNU Affero General Public License along
 // with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use alloc::string::String;
+use core::fmt;
+
 /// Errors of domain_changer
 #[derive(Debug)]
 pub enum DomainChangerError {
@@ -24,8 +27,65 @@ pub enum DomainChangerError {
     ///
     /// [`new`]: crate::types::Domain#structfield.new
     InvalidNewDomain(String),
+    /// Error mean there is no [`Domain`] with this [`old`] host
+    ///
+    /// [`Domain`]: crate::types::Domain
+    /// [`old`]: crate::types::Domain#structfield.old
+    DomainNotFound(String),
+    /// Error mean there is already a [`Group`] with this name
+    ///
+    /// [`Group`]: crate::types::Group
+    DuplicateGroup(String),
+    /// Error mean the serialized config is invalid
+    InvalidConfig(String),
+    /// Error mean the version of the serialized config is newer than the supported version
+    UnsupportedVersion(String),
+    /// Error mean the binary blob of the config is invalid or corrupted
+    InvalidBinary(String),
+    /// Error mean the [`new`] domain is not allowed by the [`SafetyPolicy`]
+    ///
+    /// [`new`]: crate::types::Domain#structfield.new
+    /// [`SafetyPolicy`]: crate::types::SafetyPolicy
+    UnsafeTarget(String),
+    /// Error mean a limit of the parsing is exceeded, see [`Limits`]
+    ///
+    /// [`Limits`]: crate::types::Limits
+    LimitExceeded(String),
+    /// Error mean the serialized config has no signature
+    Unsigned(String),
+    /// Error mean the signature is invalid or not made by a pinned key
+    BadSignature(String),
+    /// Error mean the signature is expired
+    ExpiredSignature(String),
+    /// Error mean the remote config can't be fetched and there is no cached copy of it
+    FetchFailed(String),
+    /// Error mean the config file can't be read or watched
+    ReloadFailed(String),
+}
+
+impl fmt::Display for DomainChangerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DomainChangerError::InvalidOldDomain(msg)
+            | DomainChangerError::InvalidNewDomain(msg)
+            | DomainChangerError::DomainNotFound(msg)
+            | DomainChangerError::DuplicateGroup(msg)
+            | DomainChangerError::InvalidConfig(msg)
+            | DomainChangerError::UnsupportedVersion(msg)
+            | DomainChangerError::InvalidBinary(msg)
+            | DomainChangerError::UnsafeTarget(msg)
+            | DomainChangerError::LimitExceeded(msg)
+            | DomainChangerError::Unsigned(msg)
+            | DomainChangerError::BadSignature(msg)
+            | DomainChangerError::ExpiredSignature(msg)
+            | DomainChangerError::FetchFailed(msg)
+            | DomainChangerError::ReloadFailed(msg) => write!(f, "{}", msg),
+        }
+    }
 }
 
+impl core::error::Error for DomainChangerError {}
+
 /// [`Result`] contain [`DomainChangerError`]
 pub type DomainChangerResult<T> = Result<T, DomainChangerError>;
 
@@ -58,4 +118,172 @@ impl DomainChangerError {
     pub fn is_invalid_new_domain(&self) -> bool {
         matches!(self, DomainChangerError::InvalidNewDomain(_))
     }
+
+    /// Returns if the error is [`DomainNotFound`]
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::errors::DomainChangerError::{DomainNotFound, InvalidOldDomain};
+    ///
+    /// assert!(DomainNotFound("error msg".to_owned()).is_domain_not_found());
+    /// assert!(!InvalidOldDomain("error msg".to_owned()).is_domain_not_found());
+    /// ```
+    ///
+    /// [`DomainNotFound`]: enum@DomainChangerError#variant.DomainNotFound
+    pub fn is_domain_not_found(&self) -> bool {
+        matches!(self, DomainChangerError::DomainNotFound(_))
+    }
+
+    /// Returns if the error is [`DuplicateGroup`]
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::errors::DomainChangerError::{DuplicateGroup, DomainNotFound};
+    ///
+    /// assert!(DuplicateGroup("error msg".to_owned()).is_duplicate_group());
+    /// assert!(!DomainNotFound("error msg".to_owned()).is_duplicate_group());
+    /// ```
+    ///
+    /// [`DuplicateGroup`]: enum@DomainChangerError#variant.DuplicateGroup
+    pub fn is_duplicate_group(&self) -> bool {
+        matches!(self, DomainChangerError::DuplicateGroup(_))
+    }
+
+    /// Returns if the error is [`InvalidConfig`]
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::errors::DomainChangerError::{InvalidConfig, UnsupportedVersion};
+    ///
+    /// assert!(InvalidConfig("error msg".to_owned()).is_invalid_config());
+    /// assert!(!UnsupportedVersion("error msg".to_owned()).is_invalid_config());
+    /// ```
+    ///
+    /// [`InvalidConfig`]: enum@DomainChangerError#variant.InvalidConfig
+    pub fn is_invalid_config(&self) -> bool {
+        matches!(self, DomainChangerError::InvalidConfig(_))
+    }
+
+    /// Returns if the error is [`UnsupportedVersion`]
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::errors::DomainChangerError::{InvalidConfig, UnsupportedVersion};
+    ///
+    /// assert!(UnsupportedVersion("error msg".to_owned()).is_unsupported_version());
+    /// assert!(!InvalidConfig("error msg".to_owned()).is_unsupported_version());
+    /// ```
+    ///
+    /// [`UnsupportedVersion`]: enum@DomainChangerError#variant.UnsupportedVersion
+    pub fn is_unsupported_version(&self) -> bool {
+        matches!(self, DomainChangerError::UnsupportedVersion(_))
+    }
+
+    /// Returns if the error is [`InvalidBinary`]
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::errors::DomainChangerError::{InvalidBinary, InvalidConfig};
+    ///
+    /// assert!(InvalidBinary("error msg".to_owned()).is_invalid_binary());
+    /// assert!(!InvalidConfig("error msg".to_owned()).is_invalid_binary());
+    /// ```
+    ///
+    /// [`InvalidBinary`]: enum@DomainChangerError#variant.InvalidBinary
+    pub fn is_invalid_binary(&self) -> bool {
+        matches!(self, DomainChangerError::InvalidBinary(_))
+    }
+
+    /// Returns if the error is [`UnsafeTarget`]
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::errors::DomainChangerError::{InvalidConfig, UnsafeTarget};
+    ///
+    /// assert!(UnsafeTarget("error msg".to_owned()).is_unsafe_target());
+    /// assert!(!InvalidConfig("error msg".to_owned()).is_unsafe_target());
+    /// ```
+    ///
+    /// [`UnsafeTarget`]: enum@DomainChangerError#variant.UnsafeTarget
+    pub fn is_unsafe_target(&self) -> bool {
+        matches!(self, DomainChangerError::UnsafeTarget(_))
+    }
+
+    /// Returns if the error is [`LimitExceeded`]
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::errors::DomainChangerError::{InvalidConfig, LimitExceeded};
+    ///
+    /// assert!(LimitExceeded("error msg".to_owned()).is_limit_exceeded());
+    /// assert!(!InvalidConfig("error msg".to_owned()).is_limit_exceeded());
+    /// ```
+    ///
+    /// [`LimitExceeded`]: enum@DomainChangerError#variant.LimitExceeded
+    pub fn is_limit_exceeded(&self) -> bool {
+        matches!(self, DomainChangerError::LimitExceeded(_))
+    }
+
+    /// Returns if the error is [`Unsigned`]
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::errors::DomainChangerError::{BadSignature, Unsigned};
+    ///
+    /// assert!(Unsigned("error msg".to_owned()).is_unsigned());
+    /// assert!(!BadSignature("error msg".to_owned()).is_unsigned());
+    /// ```
+    ///
+    /// [`Unsigned`]: enum@DomainChangerError#variant.Unsigned
+    pub fn is_unsigned(&self) -> bool {
+        matches!(self, DomainChangerError::Unsigned(_))
+    }
+
+    /// Returns if the error is [`BadSignature`]
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::errors::DomainChangerError::{BadSignature, Unsigned};
+    ///
+    /// assert!(BadSignature("error msg".to_owned()).is_bad_signature());
+    /// assert!(!Unsigned("error msg".to_owned()).is_bad_signature());
+    /// ```
+    ///
+    /// [`BadSignature`]: enum@DomainChangerError#variant.BadSignature
+    pub fn is_bad_signature(&self) -> bool {
+        matches!(self, DomainChangerError::BadSignature(_))
+    }
+
+    /// Returns if the error is [`ExpiredSignature`]
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::errors::DomainChangerError::{BadSignature, ExpiredSignature};
+    ///
+    /// assert!(ExpiredSignature("error msg".to_owned()).is_expired_signature());
+    /// assert!(!BadSignature("error msg".to_owned()).is_expired_signature());
+    /// ```
+    ///
+    /// [`ExpiredSignature`]: enum@DomainChangerError#variant.ExpiredSignature
+    pub fn is_expired_signature(&self) -> bool {
+        matches!(self, DomainChangerError::ExpiredSignature(_))
+    }
+
+    /// Returns if the error is [`FetchFailed`]
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::errors::DomainChangerError::{FetchFailed, InvalidConfig};
+    ///
+    /// assert!(FetchFailed("error msg".to_owned()).is_fetch_failed());
+    /// assert!(!InvalidConfig("error msg".to_owned()).is_fetch_failed());
+    /// ```
+    ///
+    /// [`FetchFailed`]: enum@DomainChangerError#variant.FetchFailed
+    pub fn is_fetch_failed(&self) -> bool {
+        matches!(self, DomainChangerError::FetchFailed(_))
+    }
+
+    /// Returns if the error is [`ReloadFailed`]
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::errors::DomainChangerError::{InvalidConfig, ReloadFailed};
+    ///
+    /// assert!(ReloadFailed("error msg".to_owned()).is_reload_failed());
+    /// assert!(!InvalidConfig("error msg".to_owned()).is_reload_failed());
+    /// ```
+    ///
+    /// [`ReloadFailed`]: enum@DomainChangerError#variant.ReloadFailed
+    pub fn is_reload_failed(&self) -> bool {
+        matches!(self, DomainChangerError::ReloadFailed(_))
+    }
 }
@@ -0,0 +1,391 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Step-by-step explanation of the matching of a word, see [`Config::explain`]
+
+use crate::canonical::email_host_span;
+use crate::scanner::{is_invisible, Words};
+use crate::types::{Config, Domain, InvisiblePolicy, Options};
+use crate::{canonicalize, is_url, normalize_host, parse_string_with, refang};
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use core::fmt;
+use url::Url;
+
+/// The explanation of the matching of a word, see [`Config::explain`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation<'a> {
+    /// The explained word
+    pub word: String,
+    /// The normalization steps of the word, in order
+    pub steps: Vec<Step>,
+    /// The considered domains, in the order of the config
+    pub domains: Vec<Considered<'a>>,
+    /// The final output of the word, see [`parse_string_with`]
+    ///
+    /// [`parse_string_with`]: crate::parse_string_with
+    pub output: String,
+}
+
+/// Normalization step of the word
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// The punctuation around the word is trimmed, as the words of the text are
+    Trimmed(String),
+    /// The word is refanged, see [`Options::refang`]
+    ///
+    /// [`Options::refang`]: crate::types::Options#structfield.refang
+    Refanged(String),
+    /// The invisible characters are stripped, see [`Options::invisible`]
+    ///
+    /// [`Options::invisible`]: crate::types::Options#structfield.invisible
+    InvisibleStripped(String),
+    /// The word has invisible characters and it is rejected, so it is not rewritten,
+    /// see [`InvisiblePolicy::Reject`]
+    ///
+    /// [`InvisiblePolicy::Reject`]: crate::types::InvisiblePolicy::Reject
+    InvisibleRejected,
+    /// The word is email address, contains its host
+    Email(String),
+    /// The email addresses are not rewritten, see [`Options::rewrite_emails`]
+    ///
+    /// [`Options::rewrite_emails`]: crate::types::Options#structfield.rewrite_emails
+    EmailSkipped,
+    /// The word is canonicalized to the url, see [`canonicalize`]
+    ///
+    /// [`canonicalize`]: crate::canonicalize
+    Canonicalized(Url),
+    /// The host is normalized through IDNA, see [`normalize_host`]
+    ///
+    /// [`normalize_host`]: crate::normalize_host
+    Host(String),
+    /// The word is not a url, so no domain is considered
+    NotUrl,
+}
+
+/// A domain that is considered for the word
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Considered<'a> {
+    /// The considered domain
+    pub domain: &'a Domain,
+    /// Why the domain matched or rejected
+    pub verdict: Verdict,
+}
+
+/// Why the domain matched or rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// The domain matched, its new host is used
+    Matched,
+    /// The [`old`] host of the domain is not the host of the word, contains the normalized
+    /// old host
+    ///
+    /// [`old`]: crate::types::Domain#structfield.old
+    HostMismatch(String),
+    /// The domain is excluded because its group is disabled, contains the group name
+    Disabled(String),
+    /// The domain matched, but a previous domain matched first (lower priority)
+    Shadowed,
+}
+
+impl Config {
+    /// Explain the matching of the `word` (url or word of the text) step by step, how the word
+    /// is normalized, the considered domains in order and why every one of them matched or
+    /// rejected, and the final output. It is the matching of [`Config::contain`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, Options, Verdict};
+    ///
+    /// let config: Config = Config::default();
+    /// let explanation = config.explain("https://m.youtube.com/watch", &Options::default());
+    /// assert_eq!(
+    ///     explanation.domains[0].verdict,
+    ///     Verdict::HostMismatch("youtube.com".to_owned())
+    /// );
+    /// assert!(explanation
+    ///     .domains
+    ///     .iter()
+    ///     .all(|considered| considered.verdict != Verdict::Matched));
+    /// assert_eq!(explanation.output, "https://m.youtube.com/watch");
+    ///
+    /// let explanation = config.explain("TWITTER.com/Awiteb", &Options::default());
+    /// assert_eq!(explanation.output, "https://nitter.net/Awiteb");
+    /// println!("{}", explanation);
+    /// ```
+    pub fn explain(&self, word: &str, options: &Options) -> Explanation<'_> {
+        let mut steps: Vec<Step> = Vec::new();
+        // The word is normalized as `parse_string_with` normalizes the words of the text
+        let Some(span) = Words::new(word).next() else {
+            steps.push(Step::NotUrl);
+            return self.explanation(word, steps, None, options);
+        };
+        let mut normalized: String = word[span].to_owned();
+        if normalized != word {
+            steps.push(Step::Trimmed(normalized.clone()));
+        }
+        let visible: String = normalized
+            .chars()
+            .filter(|chr| !is_invisible(*chr))
+            .collect();
+        if visible != normalized && is_url(&visible, options) {
+            match options.invisible {
+                InvisiblePolicy::Strip => {
+                    steps.push(Step::InvisibleStripped(visible.clone()));
+                    normalized = visible;
+                }
+                InvisiblePolicy::Reject => {
+                    steps.push(Step::InvisibleRejected);
+                    return self.explanation(word, steps, None, options);
+                }
+                InvisiblePolicy::Report => (),
+            }
+        }
+        if options.refang {
+            let refanged: String = refang(&normalized).into_owned();
+            if refanged != normalized {
+                steps.push(Step::Refanged(refanged.clone()));
+                normalized = refanged;
+            }
+        }
+        if let Some(span) = email_host_span(&normalized) {
+            steps.push(Step::Email(normalized[span.clone()].to_owned()));
+            if !options.rewrite_emails {
+                steps.push(Step::EmailSkipped);
+                return self.explanation(word, steps, None, options);
+            }
+            normalized = normalized[span].to_owned();
+        }
+
+        let url: Option<Url> = canonicalize(&normalized, &options.canonicalization);
+        match &url {
+            Some(url) => {
+                steps.push(Step::Canonicalized(url.clone()));
+                if let Some(host) = url.host_str().and_then(normalize_host) {
+                    steps.push(Step::Host(host.into_owned()));
+                }
+            }
+            None => steps.push(Step::NotUrl),
+        }
+        self.explanation(word, steps, url.as_ref(), options)
+    }
+
+    fn explanation(
+        &self,
+        word: &str,
+        steps: Vec<Step>,
+        url: Option<&Url>,
+        options: &Options,
+    ) -> Explanation<'_> {
+        let mut matched: bool = false;
+        let domains: Vec<Considered> = url
+            .map(|url| {
+                self.domains
+                    .iter()
+                    .map(|domain| {
+                        let verdict: Verdict = if !domain.matches(url, true) {
+                            Verdict::HostMismatch(
+                                domain
+                                    .old
+                                    .host_str()
+                                    .and_then(normalize_host)
+                                    .unwrap_or_default()
+                                    .into_owned(),
+                            )
+                        } else if let Some(group) =
+                            self.group_of(domain).filter(|group| !group.enabled)
+                        {
+                            Verdict::Disabled(group.name.clone())
+                        } else if matched {
+                            Verdict::Shadowed
+                        } else {
+                            matched = true;
+                            Verdict::Matched
+                        };
+                        Considered { domain, verdict }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Explanation {
+            word: word.to_owned(),
+            steps,
+            domains,
+            output: parse_string_with(self, word.to_owned(), options),
+        }
+    }
+}
+
+impl fmt::Display for Explanation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "word: {}", self.word)?;
+        for step in &self.steps {
+            match step {
+                Step::Trimmed(word) => writeln!(f, "trimmed: {}", word)?,
+                Step::Refanged(word) => writeln!(f, "refanged: {}", word)?,
+                Step::InvisibleStripped(word) => writeln!(f, "invisible stripped: {}", word)?,
+                Step::InvisibleRejected => {
+                    writeln!(f, "rejected, the word has invisible characters")?
+                }
+                Step::Email(host) => writeln!(f, "email address of: {}", host)?,
+                Step::EmailSkipped => writeln!(f, "email addresses are not rewritten")?,
+                Step::Canonicalized(url) => writeln!(f, "canonicalized: {}", url)?,
+                Step::Host(host) => writeln!(f, "host: {}", host)?,
+                Step::NotUrl => writeln!(f, "not a url")?,
+            }
+        }
+        for (index, considered) in self.domains.iter().enumerate() {
+            write!(
+                f,
+                "domain {}: {} -> {}: ",
+                index + 1,
+                considered.domain.old,
+                considered.domain.new
+            )?;
+            match &considered.verdict {
+                Verdict::Matched => writeln!(f, "matched")?,
+                Verdict::HostMismatch(host) => {
+                    writeln!(f, "host mismatch, the old host is {}", host)?
+                }
+                Verdict::Disabled(group) => writeln!(f, "excluded, group '{}' is disabled", group)?,
+                Verdict::Shadowed => writeln!(f, "matched, but lower priority")?,
+            }
+        }
+        write!(f, "output: {}", self.output)?;
+        if self.output == self.word {
+            write!(f, " (not rewritten)")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{
+        Config, Domain, Explanation, InvisiblePolicy, Options, Service, Step, Verdict,
+    };
+    use alloc::{string::ToString, vec};
+    use url::Url;
+
+    /// Returns the verdict of the considered domain with the `old` and `new` hosts
+    fn verdict<'a>(explanation: &'a Explanation<'_>, old: &str, new: &str) -> &'a Verdict {
+        &explanation
+            .domains
+            .iter()
+            .find(|considered| {
+                considered.domain.old.host_str() == Some(old)
+                    && considered.domain.new.host_str() == Some(new)
+            })
+            .unwrap()
+            .verdict
+    }
+
+    #[test]
+    fn explain_test() {
+        let mut config: Config = Config::from_services(vec![
+            Service::new(
+                "youtube",
+                &["video"],
+                &["youtube.com", "youtu.be"],
+                vec![Url::parse("https://piped.kavin.rocks").unwrap()],
+            ),
+            Service::new(
+                "twitter",
+                &["social"],
+                &["t.co", "twitter.com"],
+                vec![Url::parse("https://nitter.net").unwrap()],
+            ),
+        ])
+        .unwrap();
+        config
+            .domains
+            .push(Domain::lenient("twitter.com", "nitter.it").unwrap());
+        config.disable_group("youtube");
+
+        let explanation = config.explain("https://Twitter.com./Awiteb", &Options::default());
+        assert_eq!(
+            explanation.steps,
+            vec![
+                Step::Canonicalized("https://twitter.com/Awiteb".parse().unwrap()),
+                Step::Host("twitter.com".into())
+            ]
+        );
+        assert_eq!(
+            verdict(&explanation, "t.co", "nitter.net"),
+            &Verdict::HostMismatch("t.co".into())
+        );
+        assert_eq!(
+            verdict(&explanation, "twitter.com", "nitter.net"),
+            &Verdict::Matched
+        );
+        assert_eq!(
+            verdict(&explanation, "twitter.com", "nitter.it"),
+            &Verdict::Shadowed
+        );
+        assert_eq!(explanation.output, "https://nitter.net/Awiteb");
+
+        let explanation = config.explain("youtu.be/x", &Options::default());
+        assert_eq!(
+            verdict(&explanation, "youtu.be", "piped.kavin.rocks"),
+            &Verdict::Disabled("youtube".into())
+        );
+        assert_eq!(explanation.output, "youtu.be/x");
+        assert!(explanation
+            .to_string()
+            .ends_with("output: youtu.be/x (not rewritten)"));
+
+        let explanation = config.explain("https://", &Options::default());
+        assert_eq!(explanation.steps, vec![Step::NotUrl]);
+        assert!(explanation.domains.is_empty());
+
+        let explanation = config.explain("awiteb@twitter.com", &Options::default());
+        assert_eq!(
+            explanation.steps,
+            vec![Step::Email("twitter.com".into()), Step::EmailSkipped]
+        );
+        assert!(explanation.domains.is_empty());
+    }
+
+    #[test]
+    fn explain_normalization_test() {
+        let config: Config = Config::default();
+
+        // The punctuation is trimmed as the words of the text
+        let explanation = config.explain("twitter.com,", &Options::default());
+        assert_eq!(explanation.steps[0], Step::Trimmed("twitter.com".into()));
+        assert_eq!(explanation.steps[2], Step::Host("twitter.com".into()));
+        assert!(explanation
+            .domains
+            .iter()
+            .any(|considered| considered.verdict == Verdict::Matched));
+        assert_eq!(explanation.output, "https://nitter.net/,");
+
+        let word: &str = "twit\u{200b}ter.com";
+        let explanation = config.explain(word, &Options::default());
+        assert_eq!(
+            explanation.steps[0],
+            Step::InvisibleStripped("twitter.com".into())
+        );
+        assert_eq!(explanation.output, "https://nitter.net/");
+
+        let options: Options = Options {
+            invisible: InvisiblePolicy::Reject,
+            ..Options::default()
+        };
+        let explanation = config.explain(word, &options);
+        assert_eq!(explanation.steps, vec![Step::InvisibleRejected]);
+        assert!(explanation.domains.is_empty());
+        assert_eq!(explanation.output, word);
+    }
+}
@@ -0,0 +1,167 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+#[cfg(feature = "json")]
+use super::ToFromJson;
+use crate::host::same_host;
+use alloc::{
+    borrow::ToOwned,
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(feature = "json")]
+use serde::Deserialize;
+use url::Url;
+
+/// [`Group`] struct help you to put domains of one service together
+///
+/// The group own the [`old`] hosts of the service, and it can be disabled to
+/// stop changing all of them without removing any [`Domain`]
+///
+/// [`old`]: crate::types::Domain#structfield.old
+/// [`Domain`]: crate::types::Domain
+#[cfg_attr(feature = "json", derive(Deserialize))]
+#[cfg_attr(
+    feature = "schema",
+    derive(schemars::JsonSchema),
+    schemars(
+        description = "Group of the old hosts of one service, the disabled group will not changed"
+    )
+)]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Group {
+    /// name of the service, e.g. `youtube`
+    pub name: String,
+    /// tags (categories) of the service, e.g. `video`, `social` or `news`
+    #[cfg_attr(feature = "json", serde(default))]
+    pub tags: Vec<String>,
+    /// old hosts of the service
+    pub hosts: Vec<String>,
+    /// is the group enabled, the domains of disabled group will not changed
+    #[cfg_attr(feature = "json", serde(default = "enabled_default"))]
+    pub enabled: bool,
+    /// frontends of the service, the first one is the target and the rest are alternatives
+    #[cfg_attr(feature = "json", serde(default))]
+    pub frontends: Vec<Url>,
+}
+
+#[cfg(feature = "json")]
+fn enabled_default() -> bool {
+    true
+}
+
+impl Group {
+    /// Create new enabled [`Group`] instance
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Group;
+    ///
+    /// let group: Group = Group::new("youtube", &["video"], &["youtube.com", "youtu.be"]);
+    ///
+    /// assert_eq!(group.name, "youtube");
+    /// assert_eq!(group.hosts, vec!["youtube.com", "youtu.be"]);
+    /// assert!(group.enabled);
+    /// ```
+    pub fn new(name: &str, tags: &[&str], hosts: &[&str]) -> Self {
+        Self {
+            name: name.to_owned(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            hosts: hosts.iter().map(|host| host.to_string()).collect(),
+            enabled: true,
+            frontends: Vec::new(),
+        }
+    }
+
+    /// Returns [`true`] if the group has the `tag`
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Group;
+    ///
+    /// let group: Group = Group::new("reddit", &["social", "news"], &["reddit.com"]);
+    ///
+    /// assert!(group.has_tag("news"));
+    /// assert!(!group.has_tag("video"));
+    /// ```
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|group_tag| group_tag == tag)
+    }
+
+    /// Returns [`true`] if the `host` is one of the group [`hosts`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Group;
+    ///
+    /// let group: Group = Group::new("twitter", &["social"], &["twitter.com", "t.co"]);
+    ///
+    /// assert!(group.contain("t.co"));
+    /// assert!(!group.contain("nitter.net"));
+    /// ```
+    ///
+    /// [`hosts`]: Group#structfield.hosts
+    pub fn contain(&self, host: &str) -> bool {
+        self.hosts
+            .iter()
+            .any(|group_host| same_host(group_host, host))
+    }
+
+    /// Returns [`true`] if the `host` is the host of one of the group [`frontends`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, Service};
+    /// use url::Url;
+    ///
+    /// let config: Config = Config::from_services(vec![Service::new(
+    ///     "youtube",
+    ///     &["video"],
+    ///     &["youtube.com"],
+    ///     vec![
+    ///         Url::parse("https://piped.kavin.rocks").unwrap(),
+    ///         Url::parse("https://yewtu.be").unwrap(),
+    ///     ],
+    /// )])
+    /// .unwrap();
+    /// let group = config.get_group("youtube").unwrap();
+    ///
+    /// assert!(group.is_frontend("yewtu.be"));
+    /// assert!(!group.is_frontend("youtube.com"));
+    /// ```
+    ///
+    /// [`frontends`]: Group#structfield.frontends
+    pub fn is_frontend(&self, host: &str) -> bool {
+        self.frontends
+            .iter()
+            .any(|frontend| frontend.host_str().is_some_and(|new| same_host(new, host)))
+    }
+}
+
+/// Implementation [`ToFromJson`] to [`Group`]
+///
+/// # Example
+/// ```rust
+/// use domain_changer::types::{Group, ToFromJson};
+///
+/// let group: Group = Group::new("reddit", &["social"], &["reddit.com"]);
+/// assert_eq!(
+///     group.to_json().unwrap(),
+///     "{\"name\":\"reddit\",\"tags\":[\"social\"],\"hosts\":[\"reddit.com\"],\"enabled\":true}"
+/// );
+/// assert_eq!(Group::from_json("{\"name\":\"reddit\",\"tags\":[\"social\"],\"hosts\":[\"reddit.com\"]}").unwrap(), group);
+/// ```
+#[cfg(feature = "json")]
+impl ToFromJson<'_> for Group {}
@@ -0,0 +1,287 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Versions of the config format and the migrations between them
+//!
+//! The config document has a `version` key, the document without it is version `1`.
+//! The older documents are upgraded to [`CONFIG_VERSION`] on load, every config document
+//! is migrated before it is deserialized
+//!
+//! | Version | Format                                                                       |
+//! |---------|------------------------------------------------------------------------------|
+//! | `1`     | `{"domains": [{"old": .., "new": ..}, ..]}`, the format of the `0.1.4` release |
+//! | `1`     | the lenient forms with `groups` or `services`, or compact `{"old": "new", ..}` |
+//! | `2`     | `{"version": 2, "domains": .., "groups": ..}`                                |
+
+use super::errors::{DomainChangerError, DomainChangerResult};
+use super::repr::{self, StrictValue};
+use crate::types::Config;
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use serde_json::{Map, Value};
+
+/// The current version of the config format
+pub const CONFIG_VERSION: u64 = 2;
+
+/// [`Migration`] struct describe a migration of the config document from version to next one
+#[derive(Debug, Clone)]
+pub struct Migration {
+    /// the version of the document before the migration
+    pub from: u64,
+    /// the version of the document after the migration
+    pub to: u64,
+    /// what the migration do
+    pub description: &'static str,
+    apply: fn(&mut Map<String, Value>) -> DomainChangerResult<()>,
+}
+
+/// All migrations, ordered by [`from`] version
+///
+/// [`from`]: Migration#structfield.from
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: 1,
+    to: 2,
+    description: "add `version` key and move compact `\"old\": \"new\"` domains to `domains`",
+    apply: v1_to_v2,
+}];
+
+/// Migrate the config `document` to [`CONFIG_VERSION`], returns the migrated document
+/// and the migrations that ran. The order of the keys is kept
+///
+/// # Example
+/// ```rust
+/// use domain_changer::types::migration::{migrate, CONFIG_VERSION};
+/// use serde_json::json;
+///
+/// let (document, migrations) =
+///     migrate(json!({"youtube.com": "piped.kavin.rocks", "twitter.com": "nitter.net"})).unwrap();
+/// assert_eq!(document["version"], CONFIG_VERSION);
+/// assert_eq!(
+///     document["domains"].to_string(),
+///     r#"{"youtube.com":"piped.kavin.rocks","twitter.com":"nitter.net"}"#
+/// );
+/// assert_eq!(migrations.len(), 1);
+/// assert_eq!((migrations[0].from, migrations[0].to), (1, 2));
+///
+/// let (_, migrations) = migrate(document).unwrap();
+/// assert!(migrations.is_empty());
+///
+/// assert!(migrate(json!({"version": 100})).unwrap_err().is_unsupported_version());
+/// ```
+pub fn migrate(document: Value) -> DomainChangerResult<(Value, Vec<&'static Migration>)> {
+    let mut document: Map<String, Value> = match document {
+        Value::Object(document) => document,
+        _ => {
+            return Err(DomainChangerError::InvalidConfig(
+                "the config document should be an object".to_owned(),
+            ))
+        }
+    };
+    let version: u64 = match document.get("version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .filter(|version| *version > 0)
+            .ok_or_else(|| {
+                DomainChangerError::InvalidConfig(format!(
+                    "'{}', the version should be a positive integer",
+                    version
+                ))
+            })?,
+    };
+    if version > CONFIG_VERSION {
+        return Err(DomainChangerError::UnsupportedVersion(format!(
+            "'{}', the config version is newer than the supported version '{}'",
+            version, CONFIG_VERSION
+        )));
+    }
+
+    let mut migrations: Vec<&'static Migration> = Vec::new();
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|migration| migration.from >= version)
+    {
+        (migration.apply)(&mut document)?;
+        document.insert("version".to_owned(), Value::from(migration.to));
+        migrations.push(migration);
+    }
+    Ok((Value::Object(document), migrations))
+}
+
+/// Migrate the config `document` and deserialize it, this is the only load path of
+/// the config documents
+pub(super) fn load(document: Value) -> DomainChangerResult<(Config, Vec<&'static Migration>)> {
+    let (document, migrations) = migrate(document)?;
+    let config: Config = repr::from_migrated(document)
+        .map_err(|err| DomainChangerError::InvalidConfig(err.to_string()))?;
+    Ok((config, migrations))
+}
+
+/// Version `1` has no `version` key, and its domains can be compact `"old": "new"` pairs
+fn v1_to_v2(document: &mut Map<String, Value>) -> DomainChangerResult<()> {
+    let mut compact: Map<String, Value> = Map::new();
+    for (key, value) in core::mem::take(document) {
+        if matches!(key.as_str(), "version" | "domains" | "groups" | "services") {
+            document.insert(key, value);
+        } else {
+            compact.insert(key, value);
+        }
+    }
+    if compact.is_empty() {
+        return Ok(());
+    }
+    if document.contains_key("domains") {
+        return Err(DomainChangerError::InvalidConfig(
+            "the config can not contain `domains` with compact `\"old\": \"new\"` domains"
+                .to_owned(),
+        ));
+    }
+    document.insert("domains".to_owned(), Value::Object(compact));
+    Ok(())
+}
+
+impl Config {
+    /// Deserialize [`Config`] from json of any version, returns the config and
+    /// the migrations that ran, see [`migrate`]
+    ///
+    /// # Note
+    /// [`ToFromJson::from_json`] migrate the document too, but without the report
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, Domain};
+    ///
+    /// let (config, migrations) =
+    ///     Config::from_json_migrated("{\"domains\":[\"twitter.com -> nitter.net\"]}").unwrap();
+    /// assert_eq!(config.domains[0], Domain::lenient("twitter.com", "nitter.net").unwrap());
+    /// assert_eq!(migrations[0].to, 2);
+    /// assert!(Config::from_json_migrated("[]").unwrap_err().is_invalid_config());
+    /// ```
+    ///
+    /// [`ToFromJson::from_json`]: crate::types::ToFromJson::from_json
+    pub fn from_json_migrated(json: &str) -> DomainChangerResult<(Self, Vec<&'static Migration>)> {
+        let StrictValue(document) = serde_json::from_str(json)
+            .map_err(|err| DomainChangerError::InvalidConfig(err.to_string()))?;
+        load(document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{migrate, CONFIG_VERSION};
+    use crate::types::{Config, Domain, Group, ToFromJson};
+    use alloc::{
+        string::{String, ToString},
+        vec,
+    };
+    use serde_json::Value;
+
+    fn expected() -> Config {
+        Config::with_groups(
+            vec![
+                Domain::try_from(("https://twitter.com/", "https://nitter.net/")).unwrap(),
+                Domain::try_from(("https://youtube.com/", "https://piped.kavin.rocks/")).unwrap(),
+            ],
+            vec![Group::new("twitter", &["social"], &["twitter.com"])],
+        )
+    }
+
+    #[test]
+    fn released_fixture_test() {
+        // The format of `0.1.4`, the last release without the `version` key
+        let json: &str = include_str!("../../fixtures/config/v1_0_1_4.json");
+        let (config, ran) = Config::from_json_migrated(json).unwrap();
+        assert_eq!(ran.len(), 1);
+        assert!(config.groups.is_empty());
+        assert_eq!(config.domains, Config::default().domains);
+        assert_eq!(Config::from_json(json).unwrap(), config);
+    }
+
+    #[test]
+    fn historical_fixtures_test() {
+        for (fixture, json, migrations) in [
+            ("v1", include_str!("../../fixtures/config/v1.json"), 1),
+            (
+                "v1_lenient",
+                include_str!("../../fixtures/config/v1_lenient.json"),
+                1,
+            ),
+            (
+                "v1_compact",
+                include_str!("../../fixtures/config/v1_compact.json"),
+                1,
+            ),
+            ("v2", include_str!("../../fixtures/config/v2.json"), 0),
+        ] {
+            let (config, ran) = Config::from_json_migrated(json).unwrap();
+            assert_eq!(config, expected(), "{}", fixture);
+            assert_eq!(ran.len(), migrations, "{}", fixture);
+            assert_eq!(Config::from_json(json).unwrap(), config, "{}", fixture);
+        }
+    }
+
+    #[test]
+    fn current_version_test() {
+        let json: String = expected().to_json().unwrap();
+        let document: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(document["version"], CONFIG_VERSION);
+        assert_eq!(
+            serde_json::from_str::<Value>(include_str!("../../fixtures/config/v2.json")).unwrap(),
+            document
+        );
+        assert!(migrate(document).unwrap().1.is_empty());
+    }
+
+    #[test]
+    fn invalid_version_test() {
+        for json in [
+            r#"{"version":0,"domains":[]}"#,
+            r#"{"version":"2","domains":[]}"#,
+            r#"{"version":-1,"domains":[]}"#,
+        ] {
+            assert!(Config::from_json_migrated(json)
+                .unwrap_err()
+                .is_invalid_config());
+        }
+        assert!(Config::from_json_migrated(r#"{"version":3,"domains":[]}"#)
+            .unwrap_err()
+            .is_unsupported_version());
+        assert!(Config::from_json(r#"{"version":2,"twitter.com":"nitter.net"}"#).is_err());
+    }
+
+    #[test]
+    fn compact_order_test() {
+        let (document, _) = migrate(
+            serde_json::from_str(
+                r#"{"youtube.com":"piped.kavin.rocks","twitter.com":"nitter.net"}"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            document["domains"].to_string(),
+            r#"{"youtube.com":"piped.kavin.rocks","twitter.com":"nitter.net"}"#
+        );
+        let (config, _) = Config::from_json_migrated(
+            r#"{"youtube.com":"piped.kavin.rocks","twitter.com":"nitter.net"}"#,
+        )
+        .unwrap();
+        assert_eq!(config.old_hosts(), vec!["youtube.com", "twitter.com"]);
+    }
+}
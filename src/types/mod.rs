@@ -15,11 +15,53 @@
 // You should have received a copy of the GNU Affero General Public License along
 // with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+#[cfg(feature = "binary")]
+mod binary;
+mod builder;
 mod config;
 mod domain;
 pub mod errors;
+mod explain;
+mod group;
+#[cfg(feature = "json")]
+pub mod migration;
+mod options;
+mod policy;
+#[cfg(feature = "remote")]
+mod remote;
+mod report;
+#[cfg(feature = "json")]
+mod repr;
+#[cfg(feature = "schema")]
+pub mod schema;
+mod service;
+#[cfg(feature = "shared")]
+mod shared;
+#[cfg(feature = "signature")]
+mod signature;
 #[cfg(feature = "json")]
 mod traits;
+#[cfg(feature = "remote")]
+pub use remote::{Fetched, Source, Subscription};
+#[cfg(feature = "watch")]
+pub use shared::ConfigWatcher;
+#[cfg(feature = "shared")]
+pub use shared::SharedConfig;
+#[cfg(feature = "signature")]
+pub use signature::{PublicKey, Signature};
 #[cfg(feature = "json")]
 pub use traits::ToFromJson;
-pub use {config::Config, domain::Domain};
+pub use {
+    builder::ConfigBuilder,
+    config::Config,
+    domain::Domain,
+    explain::{Considered, Explanation, Step, Verdict},
+    group::Group,
+    options::{
+        Canonicalization, Defang, HostForm, InvisiblePolicy, Limit, LimitAction, Limits,
+        LookalikePolicy, Options, OutputMode,
+    },
+    policy::SafetyPolicy,
+    report::{Lookalike, LookalikeReason, Report, Warning, WarningKind},
+    service::Service,
+};
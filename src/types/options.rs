@@ -0,0 +1,421 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::host_to_unicode;
+use alloc::{
+    borrow::Cow,
+    format,
+    string::{String, ToString},
+};
+use core::fmt;
+use url::{Position, Url};
+
+/// [`Options`] struct help you to control how the text is parsed and rewritten,
+/// see [`parse_string_with`]
+///
+/// # Example
+/// ```rust
+/// use domain_changer::types::{HostForm, Options};
+///
+/// let options: Options = Options {
+///     host_form: HostForm::Unicode,
+///     ..Options::default()
+/// };
+/// assert_ne!(options, Options::default());
+/// ```
+///
+/// [`parse_string_with`]: crate::parse_string_with
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Options {
+    /// the form of the rewritten hosts
+    pub host_form: HostForm,
+    /// the canonicalization steps of the words, see [`canonicalize`]
+    ///
+    /// [`canonicalize`]: crate::canonicalize
+    pub canonicalization: Canonicalization,
+    /// how the rewritten urls are written in the output
+    pub output: OutputMode,
+    /// rewrite the hosts of the email addresses and the `mailto:` links,
+    /// e.g. `someone@twitter.com` will be `someone@nitter.net`
+    pub rewrite_emails: bool,
+    /// refang the defanged urls before matching, e.g. `hxxps://twitter[.]com`,
+    /// see [`refang`]
+    ///
+    /// [`refang`]: crate::refang
+    pub refang: bool,
+    /// which urls are defanged in the output, see [`defang`]
+    ///
+    /// [`defang`]: crate::defang
+    pub defang: Defang,
+    /// what to do with the urls that have invisible characters, e.g. `U+200B` and `U+202E`
+    pub invisible: InvisiblePolicy,
+    /// the limits of the parsing, see [`Limits`]
+    pub limits: Limits,
+    /// what to do with the lookalikes of the known hosts, they are detected only with
+    /// the `lookalike` feature, see [`LookalikePolicy`]
+    pub lookalike: LookalikePolicy,
+}
+
+/// [`Canonicalization`] struct is the steps of [`canonicalize`], all of them are enabled by default
+///
+/// # Example
+/// ```rust
+/// use domain_changer::canonicalize;
+/// use domain_changer::types::Canonicalization;
+///
+/// let steps: Canonicalization = Canonicalization {
+///     credentials: false,
+///     ..Canonicalization::default()
+/// };
+/// assert_eq!(
+///     canonicalize("https://user@youtube.com", &steps).unwrap().username(),
+///     "user"
+/// );
+/// ```
+///
+/// [`canonicalize`]: crate::canonicalize
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Canonicalization {
+    /// accept the `http://` and `https://` schemes in any case, e.g. `HTTPS://`
+    pub scheme_case: bool,
+    /// remove the trailing dot of the host, e.g. `youtube.com.`
+    pub trailing_dot: bool,
+    /// remove the web default ports `:80` and `:443`, the default port of the
+    /// scheme is always removed by the [`Url`] parser
+    pub default_port: bool,
+    /// remove the username and the password, e.g. `user:password@youtube.com`
+    pub credentials: bool,
+    /// decode the percent-encoded host, e.g. `you%74ube.com`, the word with
+    /// percent-encoded host is not matched if it is disabled. The encoded delimiters
+    /// like `%40` (`@`), `%3A` (`:`) and `%2F` (`/`) are never decoded
+    pub percent_decode: bool,
+}
+
+impl Canonicalization {
+    /// Returns [`Canonicalization`] with all steps disabled
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::canonicalize;
+    /// use domain_changer::types::Canonicalization;
+    ///
+    /// assert!(canonicalize("you%74ube.com", &Canonicalization::none()).is_none());
+    /// ```
+    pub const fn none() -> Self {
+        Self {
+            scheme_case: false,
+            trailing_dot: false,
+            default_port: false,
+            credentials: false,
+            percent_decode: false,
+        }
+    }
+}
+
+impl Default for Canonicalization {
+    fn default() -> Self {
+        Self {
+            scheme_case: true,
+            trailing_dot: true,
+            default_port: true,
+            credentials: true,
+            percent_decode: true,
+        }
+    }
+}
+
+/// [`HostForm`] enum is the form of the rewritten hosts
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum HostForm {
+    /// render the Unicode labels with punycode (`xn--`), e.g. `xn--bcher-kva.de`
+    #[default]
+    Punycode,
+    /// render the punycode labels as Unicode, e.g. `bücher.de`
+    Unicode,
+}
+
+impl HostForm {
+    /// Returns the `url` as string with the host in this form
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::HostForm;
+    /// use url::Url;
+    ///
+    /// let url: Url = Url::parse("https://Bücher.de/path?q").unwrap();
+    /// assert_eq!(HostForm::Punycode.render(&url), "https://xn--bcher-kva.de/path?q");
+    /// assert_eq!(HostForm::Unicode.render(&url), "https://bücher.de/path?q");
+    /// ```
+    pub fn render(&self, url: &Url) -> String {
+        match url.host_str() {
+            Some(host) => format!(
+                "{}{}{}",
+                &url[..Position::BeforeHost],
+                self.render_host(host),
+                &url[Position::AfterHost..]
+            ),
+            None => url.as_str().to_string(),
+        }
+    }
+
+    /// Returns the serialized `host` in this form
+    pub(crate) fn render_host<'a>(&self, host: &'a str) -> Cow<'a, str> {
+        match self {
+            HostForm::Unicode if host.contains("xn--") => Cow::Owned(host_to_unicode(host)),
+            _ => Cow::Borrowed(host),
+        }
+    }
+}
+
+/// [`OutputMode`] enum is how the rewritten urls are written in the output
+///
+/// # Example
+/// ```rust
+/// use domain_changer::parse_string_with;
+/// use domain_changer::types::{Config, Options, OutputMode};
+///
+/// let options: Options = Options {
+///     output: OutputMode::Minimal,
+///     ..Options::default()
+/// };
+/// assert_eq!(
+///     parse_string_with(&Config::default(), "twitter.com/Awiteb".to_owned(), &options),
+///     "nitter.net/Awiteb"
+/// );
+/// assert_eq!(
+///     parse_string_with(&Config::default(), "twitter.com/Awiteb".to_owned(), &Options::default()),
+///     "https://nitter.net/Awiteb"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum OutputMode {
+    /// write the canonicalized url, e.g. `youtube.com` will be `https://piped.kavin.rocks/`
+    #[default]
+    Reserialize,
+    /// substitute only the host in the original text, and keep the rest of it
+    /// byte-for-byte, e.g. `youtube.com` will be `piped.kavin.rocks`
+    Minimal,
+}
+
+/// [`Defang`] enum is which urls are defanged in the output, the defanged
+/// urls are not clickable, e.g. `hxxps://nitter[.]net/foo`
+///
+/// # Example
+/// ```rust
+/// use domain_changer::parse_string_with;
+/// use domain_changer::types::{Config, Defang, Options};
+///
+/// let options: Options = Options {
+///     refang: true,
+///     defang: Defang::Rewritten,
+///     ..Options::default()
+/// };
+/// assert_eq!(
+///     parse_string_with(&Config::default(), "hxxps://twitter[.]com/foo".to_owned(), &options),
+///     "hxxps://nitter[.]net/foo"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Defang {
+    /// no url is defanged
+    #[default]
+    Off,
+    /// the rewritten urls are defanged
+    Rewritten,
+    /// the rewritten urls and the other urls in the text are defanged,
+    /// a word is a url if it has a scheme or its host has a top-level domain
+    All,
+}
+
+/// [`InvisiblePolicy`] enum is what to do with the urls that have invisible characters,
+/// the zero-width characters and the bidi controls, they are always reported,
+/// see [`parse_string_report`]
+///
+/// # Example
+/// ```rust
+/// use domain_changer::parse_string_with;
+/// use domain_changer::types::{Config, InvisiblePolicy, Options};
+///
+/// let config: Config = Config::default();
+/// let text: String = "twitter\u{200b}.com/Awiteb".to_owned();
+/// let options = |invisible: InvisiblePolicy| Options {
+///     invisible,
+///     ..Options::default()
+/// };
+/// assert_eq!(
+///     parse_string_with(&config, text.clone(), &options(InvisiblePolicy::Strip)),
+///     "https://nitter.net/Awiteb"
+/// );
+/// assert_eq!(
+///     parse_string_with(&config, text, &options(InvisiblePolicy::Reject)),
+///     "twitter\u{200b}.com/Awiteb"
+/// );
+/// ```
+///
+/// [`parse_string_report`]: crate::parse_string_report
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum InvisiblePolicy {
+    /// remove the invisible characters from the url
+    #[default]
+    Strip,
+    /// leave the url as is without changing it
+    Reject,
+    /// parse the url with the invisible characters
+    Report,
+}
+
+/// [`Limits`] struct is the limits of the parsing of the untrusted text, all of
+/// them are unlimited by default, see [`Limits::untrusted`]
+///
+/// # Example
+/// ```rust
+/// use domain_changer::parse_string_report;
+/// use domain_changer::types::{Config, LimitAction, Limits, Options};
+///
+/// let options: Options = Options {
+///     limits: Limits {
+///         max_input_bytes: Some(8),
+///         exceeded: LimitAction::Error,
+///         ..Limits::default()
+///     },
+///     ..Options::default()
+/// };
+/// assert!(parse_string_report(&Config::default(), "t.co/Awiteb".to_owned(), &options)
+///     .unwrap_err()
+///     .is_limit_exceeded());
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Limits {
+    /// the maximum bytes of the text, the rest of the text is not parsed if it is truncated,
+    /// and the word that crosses the limit is kept as is
+    pub max_input_bytes: Option<usize>,
+    /// the maximum candidate urls, the words that have a dot or a scheme, the words
+    /// after the last candidate are not parsed if it is truncated
+    pub max_candidates: Option<usize>,
+    /// the maximum bytes of a url, the longer candidate urls are not parsed if it is truncated
+    pub max_url_length: Option<usize>,
+    /// the maximum rules (domains) evaluated for the whole text, the words after the
+    /// last evaluated rule are not parsed if it is truncated
+    pub max_rules: Option<usize>,
+    /// what to do if a limit is exceeded
+    pub exceeded: LimitAction,
+}
+
+impl Limits {
+    /// Returns [`Limits`] for untrusted text, 64 KiB of text, 256 candidate urls,
+    /// 2048 bytes url and 100000 rule evaluations, the text is truncated if a limit is exceeded
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Limits;
+    ///
+    /// assert_eq!(Limits::untrusted().max_url_length, Some(2048));
+    /// ```
+    pub const fn untrusted() -> Self {
+        Self {
+            max_input_bytes: Some(64 * 1024),
+            max_candidates: Some(256),
+            max_url_length: Some(2048),
+            max_rules: Some(100_000),
+            exceeded: LimitAction::Truncate,
+        }
+    }
+}
+
+/// [`LimitAction`] enum is what to do if a limit of [`Limits`] is exceeded
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum LimitAction {
+    /// stop parsing the rest of the text and keep it as is, the [`Report`] has
+    /// a warning about it
+    ///
+    /// [`Report`]: crate::types::Report
+    #[default]
+    Truncate,
+    /// returns [`LimitExceeded`] error
+    ///
+    /// [`LimitExceeded`]: crate::types::errors::DomainChangerError::LimitExceeded
+    Error,
+}
+
+/// [`Limit`] enum is a limit of [`Limits`]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Limit {
+    /// [`Limits::max_input_bytes`]
+    InputBytes,
+    /// [`Limits::max_candidates`]
+    Candidates,
+    /// [`Limits::max_url_length`]
+    UrlLength,
+    /// [`Limits::max_rules`]
+    Rules,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Limit::InputBytes => "input bytes",
+            Limit::Candidates => "candidate urls",
+            Limit::UrlLength => "url length",
+            Limit::Rules => "rules",
+        })
+    }
+}
+
+/// [`LookalikePolicy`] enum is what to do with the urls that their hosts look like
+/// the known hosts, they are always reported, see [`parse_string_report`]
+///
+/// The lookalikes are detected only with the `lookalike` feature, see `Config::lookalike_of`
+///
+/// # Example
+/// ```rust
+/// use domain_changer::parse_string_with;
+/// use domain_changer::types::{Config, LookalikePolicy, Options};
+///
+/// let config: Config = Config::default();
+/// let text: String = "twltter.com/Awiteb".to_owned();
+/// let options = |lookalike: LookalikePolicy| Options {
+///     lookalike,
+///     ..Options::default()
+/// };
+/// assert_eq!(
+///     parse_string_with(&config, text.clone(), &options(LookalikePolicy::Leave)),
+///     "twltter.com/Awiteb"
+/// );
+/// # #[cfg(feature = "lookalike")]
+/// # {
+/// assert_eq!(
+///     parse_string_with(&config, text.clone(), &options(LookalikePolicy::Annotate)),
+///     "twltter.com/Awiteb [lookalike of twitter.com]"
+/// );
+/// assert_eq!(
+///     parse_string_with(&config, text, &options(LookalikePolicy::Neutralize)),
+///     "twltter[.]com/Awiteb"
+/// );
+/// # }
+/// ```
+///
+/// [`parse_string_report`]: crate::parse_string_report
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum LookalikePolicy {
+    /// leave the url as is
+    #[default]
+    Leave,
+    /// add `[lookalike of <host>]` after the url
+    Annotate,
+    /// defang the url, see [`defang`]
+    ///
+    /// [`defang`]: crate::defang
+    Neutralize,
+}
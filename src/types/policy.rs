@@ -0,0 +1,314 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::errors::{DomainChangerError, DomainChangerResult};
+#[cfg(feature = "json")]
+use super::ToFromJson;
+use crate::host::same_host;
+use crate::types::{Config, Domain};
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::net::{Ipv4Addr, Ipv6Addr};
+use url::{Host, Url};
+
+/// [`SafetyPolicy`] struct is the rules of the [`new`] domains (the targets),
+/// the config from an untrusted source should be validated with it, see [`Config::validate`]
+///
+/// The default policy allows `https` and `http` targets on their default ports
+/// with a domain host, the IP literals and the local hosts (e.g. `localhost`) are not allowed
+///
+/// # Example
+/// ```rust
+/// use domain_changer::types::{Config, Domain, SafetyPolicy};
+///
+/// let policy: SafetyPolicy = SafetyPolicy::default();
+/// assert!(Config::default().validate(&policy).is_ok());
+///
+/// for target in [
+///     "http://192.168.1.1/",
+///     "http://localhost/",
+///     "https://nitter.net:8443/",
+///     "file:///etc/passwd",
+///     "javascript:alert(1)",
+///     "ftp://nitter.net/",
+/// ] {
+///     let config: Config = Config::new(vec![Domain::new(
+///         "https://twitter.com".parse().unwrap(),
+///         target.parse().unwrap(),
+///     )]);
+///     assert!(config.validate(&policy).unwrap_err().is_unsafe_target(), "{}", target);
+/// }
+/// ```
+///
+/// [`new`]: crate::types::Domain#structfield.new
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SafetyPolicy {
+    /// the allowed schemes of the targets, lowercase
+    pub schemes: Vec<String>,
+    /// allow the IP literals, e.g. `http://1.1.1.1/`
+    pub ip_literals: bool,
+    /// allow the private, loopback and link-local IP ranges and the local
+    /// hosts (`localhost` and `*.localhost`), the IP literals should be allowed for the ranges
+    pub private: bool,
+    /// allow the ports that are not the default port of the scheme, e.g. `https://nitter.net:8443/`
+    pub non_default_ports: bool,
+    /// the allowed hosts of the targets, all hosts are allowed if it is [`None`]
+    pub hosts: Option<Vec<String>>,
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        Self {
+            schemes: vec!["https".to_owned(), "http".to_owned()],
+            ip_literals: false,
+            private: false,
+            non_default_ports: false,
+            hosts: None,
+        }
+    }
+}
+
+impl SafetyPolicy {
+    /// Check the `target` url with the policy
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::SafetyPolicy;
+    ///
+    /// let policy: SafetyPolicy = SafetyPolicy {
+    ///     ip_literals: true,
+    ///     hosts: Some(vec!["nitter.net".to_owned(), "1.1.1.1".to_owned()]),
+    ///     ..SafetyPolicy::default()
+    /// };
+    /// assert!(policy.check(&"https://nitter.net/".parse().unwrap()).is_ok());
+    /// assert!(policy.check(&"https://1.1.1.1/".parse().unwrap()).is_ok());
+    /// assert!(policy.check(&"https://10.0.0.1/".parse().unwrap()).is_err());
+    /// assert!(policy.check(&"https://piped.kavin.rocks/".parse().unwrap()).is_err());
+    /// ```
+    pub fn check(&self, target: &Url) -> DomainChangerResult<()> {
+        if !self.schemes.iter().any(|scheme| scheme == target.scheme()) {
+            return Err(unsafe_target(target, "the scheme is not allowed"));
+        }
+        let host: Host<&str> = target
+            .host()
+            .ok_or_else(|| unsafe_target(target, "the target has no host"))?;
+        let is_private: bool = match host {
+            Host::Domain(domain) => {
+                let domain: &str = domain.strip_suffix('.').unwrap_or(domain);
+                domain == "localhost" || domain.ends_with(".localhost")
+            }
+            Host::Ipv4(ip) => {
+                if !self.ip_literals {
+                    return Err(unsafe_target(target, "the IP literals are not allowed"));
+                }
+                is_private_ipv4(ip)
+            }
+            Host::Ipv6(ip) => {
+                if !self.ip_literals {
+                    return Err(unsafe_target(target, "the IP literals are not allowed"));
+                }
+                is_private_ipv6(ip)
+            }
+        };
+        if is_private && !self.private {
+            return Err(unsafe_target(
+                target,
+                "the private and loopback hosts are not allowed",
+            ));
+        }
+        if target.port().is_some() && !self.non_default_ports {
+            return Err(unsafe_target(
+                target,
+                "the non-default ports are not allowed",
+            ));
+        }
+        if let Some(hosts) = &self.hosts {
+            let target_host: String = host.to_string();
+            if !hosts.iter().any(|host| same_host(host, &target_host)) {
+                return Err(unsafe_target(
+                    target,
+                    "the host is not in the allowed hosts",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Validate the [`new`] domains of the config with the `policy`, returns the first violation
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, SafetyPolicy};
+    ///
+    /// let mut config: Config = Config::default();
+    /// assert!(config.validate(&SafetyPolicy::default()).is_ok());
+    /// config.replace_target("t.co", "http://127.0.0.1:8080".parse().unwrap()).unwrap();
+    /// assert!(config
+    ///     .validate(&SafetyPolicy::default())
+    ///     .unwrap_err()
+    ///     .is_unsafe_target());
+    /// ```
+    ///
+    /// [`new`]: crate::types::Domain#structfield.new
+    pub fn validate(&self, policy: &SafetyPolicy) -> DomainChangerResult<()> {
+        self.domains
+            .iter()
+            .try_for_each(|domain: &Domain| policy.check(&domain.new))
+    }
+
+    /// Deserialize [`Config`] from json and validate it with the `policy`,
+    /// see [`Config::validate`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, SafetyPolicy};
+    ///
+    /// let policy: SafetyPolicy = SafetyPolicy::default();
+    /// assert!(Config::from_json_with_policy(r#"{"twitter.com":"nitter.net"}"#, &policy).is_ok());
+    /// assert!(
+    ///     Config::from_json_with_policy(r#"{"twitter.com":"http://localhost"}"#, &policy)
+    ///         .unwrap_err()
+    ///         .is_unsafe_target()
+    /// );
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn from_json_with_policy(json: &str, policy: &SafetyPolicy) -> DomainChangerResult<Self> {
+        let config: Config = Config::from_json(json)
+            .map_err(|err| DomainChangerError::InvalidConfig(err.to_string()))?;
+        config.validate(policy)?;
+        Ok(config)
+    }
+}
+
+fn is_private_ipv4(ip: Ipv4Addr) -> bool {
+    ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        // Shared address space, RFC 6598
+        || ip.octets()[0] == 100 && (ip.octets()[1] & 0b1100_0000) == 64
+}
+
+fn is_private_ipv6(ip: Ipv6Addr) -> bool {
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_unique_local()
+        || ip.is_unicast_link_local()
+        // IPv4-mapped (`::ffff:a.b.c.d`) and IPv4-compatible (`::a.b.c.d`) addresses
+        || ip.to_ipv4().is_some_and(is_private_ipv4)
+        || to_6to4(ip).is_some_and(is_private_ipv4)
+}
+
+/// Returns the wrapped IPv4 address of the 6to4 address (`2002:aabb:ccdd::/48`), RFC 3056
+fn to_6to4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    let [prefix, high, low, ..] = ip.segments();
+    (prefix == 0x2002).then(|| Ipv4Addr::from((u32::from(high) << 16) | u32::from(low)))
+}
+
+fn unsafe_target(target: &Url, reason: &str) -> DomainChangerError {
+    DomainChangerError::UnsafeTarget(format!("'{}', {}", target, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SafetyPolicy;
+    use alloc::{borrow::ToOwned, vec};
+    use url::Url;
+
+    #[test]
+    fn safety_policy_test() {
+        let check = |policy: &SafetyPolicy, target: &str| {
+            policy.check(&Url::parse(target).unwrap()).is_ok()
+        };
+        let default: SafetyPolicy = SafetyPolicy::default();
+        let ips: SafetyPolicy = SafetyPolicy {
+            ip_literals: true,
+            ..SafetyPolicy::default()
+        };
+        let local: SafetyPolicy = SafetyPolicy {
+            ip_literals: true,
+            private: true,
+            non_default_ports: true,
+            ..SafetyPolicy::default()
+        };
+
+        for target in [
+            "https://nitter.net/",
+            "http://nitter.net:80/",
+            "https://NITTER.net:443/x",
+        ] {
+            assert!(check(&default, target), "{}", target);
+        }
+        for target in [
+            "https://1.1.1.1/",
+            "http://[2606:4700::1111]/",
+            "http://[2002:101:101::1]/",
+            "http://[::8.8.8.8]/",
+            "http://8.8.8.8/",
+        ] {
+            assert!(!check(&default, target), "{}", target);
+            assert!(check(&ips, target), "{}", target);
+        }
+        for target in [
+            "http://localhost/",
+            "http://app.localhost./",
+            "http://127.0.0.1/",
+            "http://10.1.2.3/",
+            "http://172.16.0.1/",
+            "http://192.168.1.1/",
+            "http://169.254.169.254/",
+            "http://100.64.0.1/",
+            "http://0.0.0.0/",
+            "http://[::1]/",
+            "http://[fd00::1]/",
+            "http://[fe80::1]/",
+            "http://[::ffff:192.168.1.1]/",
+            "http://[::127.0.0.1]/",
+            "http://[::10.0.0.1]/",
+            "http://[2002:c0a8:101::1]/",
+            "http://[2002:7f00:1::]/",
+            "http://0x7f000001/",
+        ] {
+            assert!(!check(&ips, target), "{}", target);
+            assert!(check(&local, target), "{}", target);
+        }
+        for target in [
+            "file:///etc/passwd",
+            "javascript:alert(1)",
+            "data:text/html,hi",
+            "ftp://nitter.net/",
+        ] {
+            assert!(!check(&local, target), "{}", target);
+        }
+        assert!(!check(&default, "https://nitter.net:8443/"));
+        assert!(check(&local, "https://nitter.net:8443/"));
+
+        let allowlist: SafetyPolicy = SafetyPolicy {
+            hosts: Some(vec!["nitter.net".to_owned(), "bücher.example".to_owned()]),
+            ..SafetyPolicy::default()
+        };
+        assert!(check(&allowlist, "https://nitter.net/"));
+        assert!(check(&allowlist, "https://xn--bcher-kva.example/"));
+        assert!(!check(&allowlist, "https://evil.nitter.net/"));
+    }
+}
@@ -0,0 +1,387 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Subscription to remote [`Config`] document
+//!
+//! The document is fetched with the `ETag` and `Last-Modified` of the cached copy, so the
+//! server can respond with `304 Not Modified`. The fetched document is cached on the disk and
+//! the cached copy is used if the document can't be fetched (e.g. offline)
+
+use super::errors::{DomainChangerError, DomainChangerResult};
+use super::ToFromJson;
+use crate::types::Config;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use std::{fs, io};
+use ureq::{Agent, AgentBuilder, Response};
+
+/// The default timeout of the request
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Subscription to remote [`Config`] document, like the filter lists of the ad blockers
+///
+/// # Example
+/// ```rust,no_run
+/// use domain_changer::types::{Config, Fetched, Subscription};
+///
+/// let subscription = Subscription::new("https://example.com/config.json", "/var/cache/domain_changer");
+/// let fetched: Fetched = subscription.fetch().unwrap();
+/// // The remote rules are merged over the local rules
+/// let mut config: Config = Config::default();
+/// config.merge(fetched.config);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Subscription {
+    url: String,
+    cache_dir: PathBuf,
+    agent: Agent,
+}
+
+/// The fetched [`Config`] of the [`Subscription`]
+#[derive(Debug)]
+pub struct Fetched {
+    /// The fetched config
+    pub config: Config,
+    /// Where the config comes from
+    pub source: Source,
+}
+
+/// The source of the [`Fetched`] config
+#[derive(Debug)]
+pub enum Source {
+    /// The document is fetched from the server
+    Network,
+    /// The server responded with `304 Not Modified`, the cached copy is used
+    NotModified,
+    /// The document can't be fetched, the cached copy is used. Contains the error of fetching
+    Cache(DomainChangerError),
+}
+
+/// The cached copy of the document
+struct Cached {
+    document: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Subscription {
+    /// Create new [`Subscription`] to the `url`, the fetched document is cached in `cache_dir`
+    pub fn new(url: &str, cache_dir: impl Into<PathBuf>) -> Self {
+        Self::with_timeout(url, cache_dir, DEFAULT_TIMEOUT)
+    }
+
+    /// Same as [`Subscription::new`] but with a timeout of the request
+    pub fn with_timeout(url: &str, cache_dir: impl Into<PathBuf>, timeout: Duration) -> Self {
+        Self {
+            url: url.to_owned(),
+            cache_dir: cache_dir.into(),
+            agent: AgentBuilder::new().timeout(timeout).build(),
+        }
+    }
+
+    /// Returns the url of the subscription
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Fetch the [`Config`] document, if the document can't be fetched the cached copy
+    /// is returned with [`Source::Cache`]
+    ///
+    /// # Errors
+    /// - Returns [`FetchFailed`] error if the document can't be fetched and there is no cached copy
+    /// - Returns [`InvalidConfig`] error if the fetched document is invalid and there is no cached copy
+    ///
+    /// [`FetchFailed`]: crate::types::errors::DomainChangerError::FetchFailed
+    /// [`InvalidConfig`]: crate::types::errors::DomainChangerError::InvalidConfig
+    pub fn fetch(&self) -> DomainChangerResult<Fetched> {
+        let cached: Option<Cached> = self.read_cache();
+        self.download(cached.as_ref()).or_else(|err| {
+            match cached.map(|cached| parse(&cached.document)) {
+                Some(Ok(config)) => Ok(Fetched {
+                    config,
+                    source: Source::Cache(err),
+                }),
+                _ => Err(err),
+            }
+        })
+    }
+
+    fn download(&self, cached: Option<&Cached>) -> DomainChangerResult<Fetched> {
+        let mut request = self.agent.get(&self.url);
+        if let Some(cached) = cached {
+            if let Some(etag) = &cached.etag {
+                request = request.set("If-None-Match", etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.set("If-Modified-Since", last_modified);
+            }
+        }
+        let response: Response = request.call().map_err(|err| match err {
+            ureq::Error::Status(status, _) => DomainChangerError::FetchFailed(format!(
+                "'{}', the server responded with {}",
+                self.url, status
+            )),
+            ureq::Error::Transport(err) => {
+                DomainChangerError::FetchFailed(format!("'{}', {}", self.url, err))
+            }
+        })?;
+
+        if response.status() == 304 {
+            return match cached {
+                Some(cached) => Ok(Fetched {
+                    config: parse(&cached.document)?,
+                    source: Source::NotModified,
+                }),
+                None => Err(DomainChangerError::FetchFailed(format!(
+                    "'{}', the server responded with 304 without cached copy",
+                    self.url
+                ))),
+            };
+        }
+        let cached: Cached = Cached {
+            etag: response.header("ETag").map(str::to_owned),
+            last_modified: response.header("Last-Modified").map(str::to_owned),
+            document: response.into_string().map_err(|err| {
+                DomainChangerError::FetchFailed(format!("'{}', {}", self.url, err))
+            })?,
+        };
+        let config: Config = parse(&cached.document)?;
+        // The cache is best effort, the fetched config is returned even if it can't be cached
+        let _ = self.write_cache(&cached);
+        Ok(Fetched {
+            config,
+            source: Source::Network,
+        })
+    }
+
+    /// Returns the paths of the cached document and its metadata
+    fn cache_paths(&self) -> (PathBuf, PathBuf) {
+        // FNV-1a, the file name must be stable between the runs
+        let hash: u64 = self.url.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        });
+        (
+            self.cache_dir.join(format!("{:016x}.json", hash)),
+            self.cache_dir.join(format!("{:016x}.meta", hash)),
+        )
+    }
+
+    fn read_cache(&self) -> Option<Cached> {
+        let (document, meta) = self.cache_paths();
+        let document: String = fs::read_to_string(document).ok()?;
+        let meta: String = fs::read_to_string(meta).unwrap_or_default();
+        let field = |name: &str| {
+            meta.lines()
+                .find_map(|line| line.strip_prefix(name)?.strip_prefix(": "))
+                .map(str::to_owned)
+        };
+        Some(Cached {
+            etag: field("etag"),
+            last_modified: field("last-modified"),
+            document,
+        })
+    }
+
+    fn write_cache(&self, cached: &Cached) -> io::Result<()> {
+        let (document, meta) = self.cache_paths();
+        fs::create_dir_all(&self.cache_dir)?;
+        // The document is written first, the metadata never describes a document that is not written
+        self.replace_file(&document, cached.document.as_bytes())?;
+        let mut fields: String = String::new();
+        if let Some(etag) = &cached.etag {
+            fields.push_str(&format!("etag: {}\n", etag));
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            fields.push_str(&format!("last-modified: {}\n", last_modified));
+        }
+        self.replace_file(&meta, fields.as_bytes())
+    }
+
+    /// Write the `contents` to a temporary file in the cache directory and rename it to the
+    /// `path`, so the file is never seen half-written, even by a concurrent fetch
+    fn replace_file(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let temp: PathBuf = self.cache_dir.join(format!(
+            ".{}.{}-{}.tmp",
+            path.file_name().unwrap_or_default().to_string_lossy(),
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temp, contents)
+            .and_then(|()| fs::rename(&temp, path))
+            .inspect_err(|_| {
+                let _ = fs::remove_file(&temp);
+            })
+    }
+}
+
+fn parse(document: &str) -> DomainChangerResult<Config> {
+    Config::from_json(document).map_err(|err| DomainChangerError::InvalidConfig(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Source, Subscription};
+    use crate::types::Config;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+    use std::thread::{self, JoinHandle};
+    use tiny_http::{Header, Response, Server};
+
+    const ETAG: &str = "\"v1\"";
+    const LAST_MODIFIED: &str = "Wed, 21 Oct 2026 07:28:00 GMT";
+
+    /// Local HTTP file server, `/etag` and `/dated` serve the document with `ETag` and
+    /// `Last-Modified`, `/invalid` serves invalid document
+    struct FileServer {
+        server: Arc<Server>,
+        handle: JoinHandle<()>,
+        hits: Arc<Mutex<Vec<(String, u16)>>>,
+    }
+
+    impl FileServer {
+        fn start() -> Self {
+            let server: Arc<Server> = Arc::new(Server::http("127.0.0.1:0").unwrap());
+            let hits: Arc<Mutex<Vec<(String, u16)>>> = Arc::default();
+            let handle: JoinHandle<()> = thread::spawn({
+                let (server, hits) = (Arc::clone(&server), Arc::clone(&hits));
+                move || {
+                    for request in server.incoming_requests() {
+                        let header = |name: &'static str| {
+                            request
+                                .headers()
+                                .iter()
+                                .find(|header| header.field.equiv(name))
+                                .map(|header| header.value.to_string())
+                        };
+                        let (validator, value) = match request.url() {
+                            "/etag" => ("ETag", ETAG),
+                            _ => ("Last-Modified", LAST_MODIFIED),
+                        };
+                        let not_modified: bool = header("If-None-Match").as_deref() == Some(ETAG)
+                            || header("If-Modified-Since").as_deref() == Some(LAST_MODIFIED);
+                        let (status, body) = match request.url() {
+                            "/invalid" => (200, "{\"twitter.com\":1}"),
+                            "/etag" | "/dated" if not_modified => (304, ""),
+                            "/etag" | "/dated" => (200, r#"{"twitter.com":"nitter.it"}"#),
+                            _ => (404, ""),
+                        };
+                        hits.lock()
+                            .unwrap()
+                            .push((request.url().to_owned(), status));
+                        let response = Response::from_string(body)
+                            .with_status_code(status)
+                            .with_header(Header::from_bytes(validator, value).unwrap());
+                        request.respond(response).unwrap();
+                    }
+                }
+            });
+            Self {
+                server,
+                handle,
+                hits,
+            }
+        }
+
+        fn url(&self, path: &str) -> String {
+            format!("http://{}{}", self.server.server_addr(), path)
+        }
+
+        fn last_status(&self) -> u16 {
+            self.hits.lock().unwrap().last().unwrap().1
+        }
+
+        /// Stop the server and close its listener
+        fn stop(self) {
+            self.server.unblock();
+            self.handle.join().unwrap();
+            drop(self.server);
+        }
+    }
+
+    /// Temporary cache directory, it is removed when it is dropped
+    struct CacheDir(PathBuf);
+
+    impl Drop for CacheDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn cache_dir(name: &str) -> CacheDir {
+        let dir: PathBuf =
+            std::env::temp_dir().join(format!("domain_changer-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        CacheDir(dir)
+    }
+
+    #[test]
+    fn subscription_test() {
+        let server: FileServer = FileServer::start();
+        let expected: Config = Config::builder()
+            .mapping("https://twitter.com", "https://nitter.it")
+            .build()
+            .unwrap();
+
+        let etag_cache: CacheDir = cache_dir("etag");
+        let dated_cache: CacheDir = cache_dir("dated");
+        for (path, cache) in [("/etag", &etag_cache.0), ("/dated", &dated_cache.0)] {
+            let subscription: Subscription = Subscription::new(&server.url(path), cache);
+            let fetched = subscription.fetch().unwrap();
+            assert!(matches!(fetched.source, Source::Network), "{}", path);
+            assert_eq!(fetched.config, expected);
+            assert_eq!(server.last_status(), 200);
+            // Only the document and its metadata are left, the temporary files are renamed
+            let names: Vec<String> = std::fs::read_dir(cache)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+                .collect();
+            assert_eq!(names.len(), 2);
+            assert!(names
+                .iter()
+                .all(|name| name.ends_with(".json") || name.ends_with(".meta")));
+
+            let fetched = subscription.fetch().unwrap();
+            assert!(matches!(fetched.source, Source::NotModified), "{}", path);
+            assert_eq!(fetched.config, expected);
+            assert_eq!(server.last_status(), 304);
+        }
+
+        // The invalid document is not cached
+        let invalid_cache: CacheDir = cache_dir("invalid");
+        let subscription: Subscription =
+            Subscription::new(&server.url("/invalid"), &invalid_cache.0);
+        assert!(subscription.fetch().unwrap_err().is_invalid_config());
+        assert!(subscription.read_cache().is_none());
+        let missing_cache: CacheDir = cache_dir("missing");
+        assert!(Subscription::new(&server.url("/missing"), &missing_cache.0)
+            .fetch()
+            .unwrap_err()
+            .is_fetch_failed());
+
+        // Offline, the cached copy is used
+        let url: String = server.url("/etag");
+        server.stop();
+        let fetched = Subscription::new(&url, &etag_cache.0).fetch().unwrap();
+        assert!(matches!(fetched.source, Source::Cache(ref err) if err.is_fetch_failed()));
+        assert_eq!(fetched.config, expected);
+        let offline_cache: CacheDir = cache_dir("offline");
+        assert!(Subscription::new(&url, &offline_cache.0)
+            .fetch()
+            .unwrap_err()
+            .is_fetch_failed());
+    }
+}
@@ -0,0 +1,83 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::types::Limit;
+use alloc::{string::String, vec::Vec};
+use core::ops::Range;
+
+/// [`Report`] struct is the result of [`parse_string_report`], the new text and
+/// the warnings about the original text
+///
+/// [`parse_string_report`]: crate::parse_string_report
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Report {
+    /// the new text, same as the result of [`parse_string_with`]
+    ///
+    /// [`parse_string_with`]: crate::parse_string_with
+    pub text: String,
+    /// the warnings, ordered by their spans
+    pub warnings: Vec<Warning>,
+}
+
+/// [`Warning`] struct is a warning about a part of the original text
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Warning {
+    /// the byte range of the warned part in the original text
+    pub span: Range<usize>,
+    /// what is wrong with the part
+    pub kind: WarningKind,
+}
+
+/// [`WarningKind`] enum is what is wrong with the warned part
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// an invisible character inside a url, see [`InvisiblePolicy`]
+    ///
+    /// [`InvisiblePolicy`]: crate::types::InvisiblePolicy
+    Invisible(char),
+    /// the limit is exceeded and the part is not parsed, see [`Limits`]
+    ///
+    /// [`Limits`]: crate::types::Limits
+    LimitExceeded(Limit),
+    /// the host of the url looks like a known host, see [`Lookalike`], it is reported
+    /// only with the `lookalike` feature
+    Lookalike(Lookalike),
+}
+
+/// [`Lookalike`] struct is a host that looks like a known host of the config,
+/// the [`old`] or the [`new`] host of a domain
+///
+/// [`old`]: crate::types::Domain#structfield.old
+/// [`new`]: crate::types::Domain#structfield.new
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Lookalike {
+    /// the lookalike host, in its Unicode form
+    pub host: String,
+    /// the known host that it looks like
+    pub of: String,
+    /// why it looks like the known host
+    pub reason: LookalikeReason,
+}
+
+/// [`LookalikeReason`] enum is why the host looks like the known host
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LookalikeReason {
+    /// the hosts have the same [confusable skeleton](https://www.unicode.org/reports/tr39/#Confusable_Detection),
+    /// e.g. `reddit.com` with Cyrillic `а`
+    Confusable,
+    /// the skeletons of the hosts are different by the edit distance, e.g. `twltter.com`
+    EditDistance(usize),
+}
@@ -0,0 +1,504 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The lenient (human-friendly) deserialized form of [`Domain`] and [`Config`]
+//!
+//! [`Domain`] can be deserialized from:
+//! - `{"old": "https://twitter.com/", "new": "https://nitter.net/"}`
+//! - `{"old": "twitter.com", "new": "nitter.net"}`
+//! - `"twitter.com -> nitter.net"`
+//!
+//! [`Config`] can be deserialized from:
+//! - `{"version": 2, "domains": [<domain>, ..], "groups": [..]}`
+//! - `{"version": 2, "domains": {"twitter.com": "nitter.net", ..}, "groups": [..]}`
+//! - `{"version": 2, "services": [<service>, ..]}`, the services are compiled after the domains
+//! - older versions of the config format, see [`migration`]
+//!
+//! The config that is compiled from its services only is serialized to the `services` form
+//!
+//! The lenient forms are for the self-describing (human-readable) formats, the other formats
+//! (e.g. postcard or bincode) use the canonical form of the current version
+//!
+//! [`migration`]: crate::types::migration
+
+use super::migration::{self, CONFIG_VERSION};
+use crate::types::{Config, Domain, Group, Service};
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+use core::fmt;
+use serde::de::{self, Deserializer, IgnoredAny, MapAccess, SeqAccess, Unexpected, Visitor};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// The fields of the canonical form of [`Config`]
+const CONFIG_FIELDS: &[&str] = &["version", "domains", "groups"];
+/// The fields of the config document, the canonical fields and the services
+const DOCUMENT_FIELDS: &[&str] = &["version", "domains", "groups", "services"];
+
+impl<'de> Deserialize<'de> for Domain {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(DomainVisitor {
+                human_readable: true,
+            })
+        } else {
+            deserializer.deserialize_struct(
+                "Domain",
+                &["old", "new"],
+                DomainVisitor {
+                    human_readable: false,
+                },
+            )
+        }
+    }
+}
+
+/// The sequence `(old, new)` is the canonical form of the non-self-describing formats only,
+/// the human-readable formats reject it
+struct DomainVisitor {
+    human_readable: bool,
+}
+
+impl<'de> Visitor<'de> for DomainVisitor {
+    type Value = Domain;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(r#"a domain, `{"old": .., "new": ..}` or `"old -> new"`"#)
+    }
+
+    fn visit_str<E>(self, domains: &str) -> Result<Domain, E>
+    where
+        E: de::Error,
+    {
+        domains.parse().map_err(E::custom)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Domain, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        if self.human_readable {
+            return Err(de::Error::invalid_type(Unexpected::Seq, &self));
+        }
+        let old: String = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let new: String = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Domain::lenient(&old, &new).map_err(de::Error::custom)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Domain, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let (mut old, mut new): (Option<String>, Option<String>) = (None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "old" if old.is_some() => return Err(de::Error::duplicate_field("old")),
+                "new" if new.is_some() => return Err(de::Error::duplicate_field("new")),
+                "old" => old = Some(map.next_value()?),
+                "new" => new = Some(map.next_value()?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        let old: String = old.ok_or_else(|| de::Error::missing_field("old"))?;
+        let new: String = new.ok_or_else(|| de::Error::missing_field("new"))?;
+        Domain::lenient(&old, &new).map_err(de::Error::custom)
+    }
+}
+
+/// The domains of the config, a list of domains or a map from old to new
+struct Domains(Vec<Domain>);
+
+impl<'de> Deserialize<'de> for Domains {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(DomainsVisitor)
+        } else {
+            deserializer.deserialize_seq(DomainsVisitor)
+        }
+    }
+}
+
+struct DomainsVisitor;
+
+impl<'de> Visitor<'de> for DomainsVisitor {
+    type Value = Domains;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(r#"a list of domains or a map of `"old": "new"`"#)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Domains, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut domains: Vec<Domain> = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(domain) = seq.next_element()? {
+            domains.push(domain);
+        }
+        Ok(Domains(domains))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Domains, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut domains: Vec<Domain> = Vec::with_capacity(map.size_hint().unwrap_or_default());
+        while let Some((old, new)) = map.next_entry::<String, String>()? {
+            domains.push(Domain::lenient(&old, &new).map_err(de::Error::custom)?);
+        }
+        Ok(Domains(domains))
+    }
+}
+
+impl Serialize for Config {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            let services: Vec<Service> = self.services();
+            if !services.is_empty()
+                && Config::from_services(services.clone()).is_ok_and(|config| config == *self)
+            {
+                let mut config = serializer.serialize_struct("Config", 2)?;
+                config.serialize_field("version", &CONFIG_VERSION)?;
+                config.serialize_field("services", &services)?;
+                return config.end();
+            }
+        }
+        // The empty groups are omitted from the human-readable formats, the other
+        // formats have no field names so all fields are serialized
+        let groups: bool = !self.groups.is_empty() || !serializer.is_human_readable();
+        let mut config = serializer.serialize_struct("Config", if groups { 3 } else { 2 })?;
+        config.serialize_field("version", &CONFIG_VERSION)?;
+        config.serialize_field("domains", &self.domains)?;
+        if groups {
+            config.serialize_field("groups", &self.groups)?;
+        }
+        config.end()
+    }
+}
+
+impl Serialize for Group {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // The empty frontends are omitted from the human-readable formats
+        let frontends: bool = !self.frontends.is_empty() || !serializer.is_human_readable();
+        let mut group = serializer.serialize_struct("Group", if frontends { 5 } else { 4 })?;
+        group.serialize_field("name", &self.name)?;
+        group.serialize_field("tags", &self.tags)?;
+        group.serialize_field("hosts", &self.hosts)?;
+        group.serialize_field("enabled", &self.enabled)?;
+        if frontends {
+            group.serialize_field("frontends", &self.frontends)?;
+        }
+        group.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Config {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let StrictValue(document) = StrictValue::deserialize(deserializer)?;
+            migration::load(document)
+                .map(|(config, _)| config)
+                .map_err(de::Error::custom)
+        } else {
+            deserializer.deserialize_struct("Config", CONFIG_FIELDS, ConfigVisitor)
+        }
+    }
+}
+
+/// Deserialize the canonical form of the current version, the human-readable documents
+/// reach it after [`migrate`]
+///
+/// [`migrate`]: crate::types::migration::migrate
+pub(super) fn from_migrated(document: Value) -> serde_json::Result<Config> {
+    document.deserialize_map(ConfigVisitor)
+}
+
+/// Deserialize the canonical form of the current version
+struct ConfigVisitor;
+
+impl<'de> Visitor<'de> for ConfigVisitor {
+    type Value = Config;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a config")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Config, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let version: u64 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        check_version(version)?;
+        let Domains(domains) = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let groups: Vec<Group> = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        Ok(Config::with_groups(domains, groups))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Config, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut version: Option<u64> = None;
+        let mut domains: Option<Domains> = None;
+        let mut groups: Option<Vec<Group>> = None;
+        let mut services: Option<Vec<Service>> = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "version" if version.is_some() => {
+                    return Err(de::Error::duplicate_field("version"))
+                }
+                "domains" if domains.is_some() => {
+                    return Err(de::Error::duplicate_field("domains"))
+                }
+                "groups" if groups.is_some() => return Err(de::Error::duplicate_field("groups")),
+                "services" if services.is_some() => {
+                    return Err(de::Error::duplicate_field("services"))
+                }
+                "version" => version = Some(map.next_value()?),
+                "domains" => domains = Some(map.next_value()?),
+                "groups" => groups = Some(map.next_value()?),
+                "services" => services = Some(map.next_value()?),
+                _ => return Err(de::Error::unknown_field(&key, DOCUMENT_FIELDS)),
+            }
+        }
+        check_version(version.ok_or_else(|| de::Error::missing_field("version"))?)?;
+        let mut config: Config = match (domains, groups) {
+            (Some(Domains(domains)), groups) => {
+                Config::with_groups(domains, groups.unwrap_or_default())
+            }
+            (None, Some(_)) => return Err(de::Error::missing_field("domains")),
+            (None, None) => Config::new(Vec::new()),
+        };
+        for service in services.unwrap_or_default() {
+            config.add_service(service).map_err(de::Error::custom)?;
+        }
+        Ok(config)
+    }
+}
+
+/// [`Value`] that rejects the duplicate keys of the objects, [`Value`] keeps the last one of them
+pub(super) struct StrictValue(pub(super) Value);
+
+impl<'de> Deserialize<'de> for StrictValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(StrictValueVisitor)
+    }
+}
+
+struct StrictValueVisitor;
+
+impl<'de> Visitor<'de> for StrictValueVisitor {
+    type Value = StrictValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any valid value")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<StrictValue, E> {
+        Ok(StrictValue(Value::Bool(value)))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<StrictValue, E> {
+        Ok(StrictValue(Value::from(value)))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<StrictValue, E> {
+        Ok(StrictValue(Value::from(value)))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<StrictValue, E> {
+        Ok(StrictValue(Value::from(value)))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<StrictValue, E> {
+        Ok(StrictValue(Value::String(value.to_owned())))
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<StrictValue, E> {
+        Ok(StrictValue(Value::String(value)))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<StrictValue, E> {
+        Ok(StrictValue(Value::Null))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<StrictValue, E> {
+        Ok(StrictValue(Value::Null))
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<StrictValue, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        StrictValue::deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<StrictValue, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values: Vec<Value> = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(StrictValue(value)) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(StrictValue(Value::Array(values)))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<StrictValue, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut values: Map<String, Value> = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if values.contains_key(&key) {
+                return Err(de::Error::custom(format!("duplicate field `{}`", key)));
+            }
+            let StrictValue(value) = map.next_value()?;
+            values.insert(key, value);
+        }
+        Ok(StrictValue(Value::Object(values)))
+    }
+}
+
+/// Check the `version` of the canonical form, it should be [`CONFIG_VERSION`]
+fn check_version<E: de::Error>(version: u64) -> Result<(), E> {
+    if version != CONFIG_VERSION {
+        return Err(E::custom(format!(
+            "'{}', expected the config version '{}'",
+            version, CONFIG_VERSION
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{Config, Domain, ToFromJson};
+    use alloc::{string::String, vec, vec::Vec};
+
+    #[test]
+    fn config_lenient_forms_test() {
+        let config: Config = Config::new(vec![
+            Domain::try_from(("https://twitter.com/", "https://nitter.net/")).unwrap(),
+            Domain::try_from(("https://youtube.com/", "https://piped.kavin.rocks/")).unwrap(),
+        ]);
+
+        for json in [
+            r#"{"domains":[{"old":"https://twitter.com/","new":"https://nitter.net/"},{"old":"https://youtube.com/","new":"https://piped.kavin.rocks/"}]}"#,
+            r#"{"domains":[{"old":"twitter.com","new":"nitter.net"},"youtube.com -> piped.kavin.rocks"]}"#,
+            r#"{"domains":{"twitter.com":"nitter.net","youtube.com":"piped.kavin.rocks"}}"#,
+            r#"{"twitter.com":"nitter.net","youtube.com":"https://piped.kavin.rocks"}"#,
+        ] {
+            assert_eq!(Config::from_json(json).unwrap(), config, "{}", json);
+        }
+        // The order of the compact form is kept
+        assert_eq!(
+            Config::from_json(r#"{"youtube.com":"piped.kavin.rocks","twitter.com":"nitter.net"}"#)
+                .unwrap()
+                .old_hosts(),
+            vec!["youtube.com", "twitter.com"]
+        );
+        // The canonical form
+        assert_eq!(
+            Config::from_json(&config.to_json().unwrap()).unwrap(),
+            config
+        );
+    }
+
+    #[test]
+    fn config_lenient_errors_test() {
+        assert!(Config::from_json(r#"{"domains":[],"twitter.com":"nitter.net"}"#).is_err());
+        assert!(Config::from_json(r#"{"groups":[]}"#).is_err());
+        assert!(Config::from_json(r#"{"twitter.com":"nitter .net"}"#).is_err());
+        assert!(Config::from_json(r#"{"domains":["twitter.com"]}"#).is_err());
+        assert!(Config::from_json(r#"{"domains":[["twitter.com","nitter.net"]]}"#).is_err());
+        assert!(Config::from_json(r#"[]"#).is_err());
+        assert!(Config::from_json(r#"{"version":2,"domains":[],"foo":1}"#).is_err());
+        assert_eq!(Config::from_json("{}").unwrap(), Config::new(Vec::new()));
+        assert!(Config::from_json(r#"{"version":3,"domains":[]}"#).is_err());
+        assert!(Config::from_json(r#"{"version":0,"domains":[]}"#).is_err());
+        assert!(Config::from_json(r#"{"domains":[],"domains":[]}"#).is_err());
+    }
+
+    #[test]
+    fn config_services_form_test() {
+        let config: Config = Config::default();
+        let json: String = config.to_json().unwrap();
+        assert!(json.starts_with(r#"{"version":2,"services":[{"name":"youtube","#));
+        assert_eq!(Config::from_json(&json).unwrap(), config);
+
+        // The config that is not compiled from its services only
+        let mut config: Config = Config::default();
+        config.disable_group("reddit");
+        let json: String = config.to_json().unwrap();
+        assert!(json.starts_with(r#"{"version":2,"domains":["#));
+        assert_eq!(Config::from_json(&json).unwrap(), config);
+
+        // The services are compiled after the domains
+        let config: Config = Config::from_json(
+            r#"{"domains":["t.co -> nitter.net"],"services":[{"name":"youtube","hosts":["youtu.be"],"frontends":["https://piped.kavin.rocks/","https://yewtu.be/"]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(config.old_hosts(), vec!["t.co", "youtu.be"]);
+        assert_eq!(config.get_group("youtube").unwrap().frontends.len(), 2);
+        assert!(Config::from_json(
+            r#"{"domains":[],"groups":[{"name":"youtube","hosts":[]}],"services":[{"name":"youtube","hosts":[],"frontends":[]}]}"#
+        )
+        .is_err());
+    }
+
+    #[cfg(feature = "binary")]
+    #[test]
+    fn config_non_self_describing_test() {
+        let mut config: Config = Config::default();
+        config.disable_group("reddit");
+        let bytes: Vec<u8> = postcard::to_allocvec(&config).unwrap();
+        assert_eq!(postcard::from_bytes::<Config>(&bytes).unwrap(), config);
+
+        let empty: Config = Config::new(Vec::new());
+        let bytes: Vec<u8> = postcard::to_allocvec(&empty).unwrap();
+        assert_eq!(postcard::from_bytes::<Config>(&bytes).unwrap(), empty);
+    }
+}
@@ -0,0 +1,238 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! [JSON Schema](https://json-schema.org/) of the config format
+//!
+//! The schemas of [`Domain`] and [`Config`] describe all forms accepted by the
+//! deserializer, see [`ToFromJson`] implementation of them
+//!
+//! [`ToFromJson`]: crate::types::ToFromJson
+
+use super::migration::CONFIG_VERSION;
+use crate::types::{Config, Domain, Group, Service};
+use alloc::{borrow::Cow, vec::Vec};
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+impl JsonSchema for Domain {
+    fn schema_name() -> Cow<'static, str> {
+        "Domain".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "Domain to change the old domain to the new one, the domains can be urls or bare hosts",
+            "oneOf": [
+                {
+                    "type": "object",
+                    "properties": {
+                        "old": {
+                            "description": "old domain to change it",
+                            "type": "string",
+                            "pattern": "\\S"
+                        },
+                        "new": {
+                            "description": "new domain you want change to it",
+                            "type": "string",
+                            "pattern": "\\S"
+                        }
+                    },
+                    "required": ["old", "new"]
+                },
+                {
+                    "description": "`old -> new` form, the domains are split at the first `->`",
+                    "type": "string",
+                    "pattern": "^\\s*([^\\s-]|-[^>]).*->.*\\S"
+                }
+            ]
+        })
+    }
+}
+
+impl JsonSchema for Config {
+    fn schema_name() -> Cow<'static, str> {
+        "Config".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let domain: Schema = generator.subschema_for::<Domain>();
+        let groups: Schema = generator.subschema_for::<Vec<Group>>();
+        let services: Schema = generator.subschema_for::<Vec<Service>>();
+        let version = |maximum: u64| {
+            json_schema!({
+                "description": "version of the config format, the config without it is version 1",
+                "type": "integer",
+                "minimum": 1,
+                "maximum": maximum
+            })
+        };
+        json_schema!({
+            "description": "Config of the domains, the compact form (version 1) is a map of `\"old\": \"new\"`",
+            "anyOf": [
+                {
+                    "type": "object",
+                    "properties": {
+                        "version": version(CONFIG_VERSION),
+                        "domains": {
+                            "oneOf": [
+                                {
+                                    "type": "array",
+                                    "items": domain
+                                },
+                                {
+                                    "type": "object",
+                                    "additionalProperties": { "type": "string", "pattern": "\\S" }
+                                }
+                            ]
+                        },
+                        "groups": groups,
+                        "services": services
+                    },
+                    "dependentRequired": { "groups": ["domains"] },
+                    "additionalProperties": false
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "version": version(1),
+                        "domains": false,
+                        "groups": groups,
+                        "services": services
+                    },
+                    "additionalProperties": { "type": "string", "pattern": "\\S" },
+                    "if": { "required": ["groups"] },
+                    "then": { "not": { "propertyNames": { "enum": ["version", "groups", "services"] } } }
+                }
+            ]
+        })
+    }
+}
+
+impl Config {
+    /// Returns the [JSON Schema](https://json-schema.org/) of the config format,
+    /// it is the same schema of `schema/config.schema.json`
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Config;
+    ///
+    /// let schema = Config::schema();
+    /// assert_eq!(schema.get("title").unwrap(), "Config");
+    /// assert!(schema.get("$defs").unwrap().get("Domain").is_some());
+    /// ```
+    pub fn schema() -> Schema {
+        schemars::schema_for!(Config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{Config, ToFromJson};
+    use alloc::string::String;
+    use serde_json::Value;
+
+    /// Documents accepted by the deserializer
+    const VALID: &[&str] = &[
+        r#"{}"#,
+        r#"{"domains":[]}"#,
+        r#"{"domains":[{"old":"https://twitter.com/","new":"https://nitter.net/"}]}"#,
+        r#"{"domains":[{"old":"twitter.com","new":"nitter.net"}]}"#,
+        r#"{"domains":["twitter.com -> nitter.net"]}"#,
+        r#"{"domains":{"twitter.com":"nitter.net"}}"#,
+        r#"{"twitter.com":"nitter.net","youtube.com":"piped.kavin.rocks"}"#,
+        r#"{"twitter.com":"nitter.net","groups":[{"name":"twitter","hosts":["twitter.com"]}]}"#,
+        r#"{"domains":["t.co -> nitter.net"],"groups":[{"name":"twitter","tags":["social"],"hosts":["t.co"],"enabled":false}]}"#,
+        r#"{"version":1,"domains":["t.co -> nitter.net"]}"#,
+        r#"{"version":2,"domains":{"t.co":"nitter.net"}}"#,
+        r#"{"version":1,"twitter.com":"nitter.net"}"#,
+        r#"{"version":1}"#,
+        r#"{"version":2}"#,
+        r#"{"domains":["t.co->nitter.net"]}"#,
+        r#"{"twitter.com":"nitter.net","services":[]}"#,
+        r#"{"version":2,"services":[{"name":"twitter","hosts":["twitter.com"],"frontends":["https://nitter.net/"]}]}"#,
+        r#"{"domains":["t.co -> nitter.net"],"services":[{"name":"youtube","tags":["video"],"hosts":["youtu.be"],"frontends":[]}]}"#,
+        include_str!("../../fixtures/config/v1_0_1_4.json"),
+        include_str!("../../fixtures/config/v1.json"),
+        include_str!("../../fixtures/config/v1_lenient.json"),
+        include_str!("../../fixtures/config/v1_compact.json"),
+        include_str!("../../fixtures/config/v2.json"),
+    ];
+
+    /// Documents rejected by the deserializer
+    const INVALID: &[&str] = &[
+        r#"[]"#,
+        r#"{"groups":[]}"#,
+        r#"{"domains":["twitter.com"]}"#,
+        r#"{"domains":[{"old":"twitter.com"}]}"#,
+        r#"{"domains":[],"twitter.com":"nitter.net"}"#,
+        r#"{"twitter.com":1}"#,
+        r#"{"domains":[],"groups":[{"hosts":[]}]}"#,
+        r#"{"version":3,"domains":[]}"#,
+        r#"{"version":0,"domains":[]}"#,
+        r#"{"version":2,"twitter.com":"nitter.net"}"#,
+        r#"{"version":2,"groups":[]}"#,
+        r#"{"version":1,"groups":[]}"#,
+        r#"{"domains":[["twitter.com","nitter.net"]]}"#,
+        r#"{"domains":[" -> nitter.net"]}"#,
+        r#"{"domains":["twitter.com -> "]}"#,
+        r#"{"domains":[{"old":" ","new":"nitter.net"}]}"#,
+        r#"{"domains":{"twitter.com":" "}}"#,
+        r#"{"twitter.com":" "}"#,
+        r#"{"services":[{"name":"twitter","hosts":["twitter.com"]}]}"#,
+        r#"{"groups":[],"services":[]}"#,
+    ];
+
+    fn validator() -> jsonschema::Validator {
+        jsonschema::validator_for(&serde_json::to_value(Config::schema()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn schema_accepts_deserializer_test() {
+        let validator = validator();
+        for json in VALID {
+            assert!(Config::from_json(json).is_ok(), "{}", json);
+            assert!(
+                validator.is_valid(&serde_json::from_str::<Value>(json).unwrap()),
+                "{}",
+                json
+            );
+        }
+        let default_json: String = Config::default().to_json().unwrap();
+        assert!(validator.is_valid(&serde_json::from_str::<Value>(&default_json).unwrap()));
+    }
+
+    #[test]
+    fn schema_rejects_test() {
+        let validator = validator();
+        for json in INVALID {
+            assert!(Config::from_json(json).is_err(), "{}", json);
+            assert!(
+                !validator.is_valid(&serde_json::from_str::<Value>(json).unwrap()),
+                "{}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn shipped_schema_test() {
+        let shipped: Value =
+            serde_json::from_str(include_str!("../../schema/config.schema.json")).unwrap();
+        assert_eq!(
+            shipped,
+            serde_json::to_value(Config::schema()).unwrap(),
+            "`schema/config.schema.json` is outdated"
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::errors::{DomainChangerError, DomainChangerResult};
+#[cfg(feature = "json")]
+use super::ToFromJson;
+use crate::host::same_host;
+use crate::types::{Domain, Group};
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// [`Service`] struct describe one service with many [`hosts`] and many [`frontends`]
+///
+/// The service is compiled to [`Domain`]s, every host is changed to the first frontend,
+/// and the other frontends are alternatives, they are kept in the [`Group`] of the service
+///
+/// [`hosts`]: Service#structfield.hosts
+/// [`frontends`]: Service#structfield.frontends
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "schema",
+    derive(schemars::JsonSchema),
+    schemars(
+        description = "Service with many hosts and many frontends, the hosts are changed to the first frontend"
+    )
+)]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Service {
+    /// name of the service, e.g. `youtube`
+    pub name: String,
+    /// tags (categories) of the service, e.g. `video`, `social` or `news`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub tags: Vec<String>,
+    /// source hosts (and aliases) of the service, e.g. `youtube.com` and `youtu.be`
+    pub hosts: Vec<String>,
+    /// frontends of the service, the first one is the target
+    pub frontends: Vec<Url>,
+}
+
+impl Service {
+    /// Create new [`Service`] instance
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Service;
+    /// use url::Url;
+    ///
+    /// let service: Service = Service::new(
+    ///     "twitter",
+    ///     &["social"],
+    ///     &["twitter.com", "t.co"],
+    ///     vec![Url::parse("https://nitter.net").unwrap()],
+    /// );
+    ///
+    /// assert_eq!(service.hosts, vec!["twitter.com", "t.co"]);
+    /// assert_eq!(service.target().unwrap().as_str(), "https://nitter.net/");
+    /// ```
+    pub fn new(name: &str, tags: &[&str], hosts: &[&str], frontends: Vec<Url>) -> Self {
+        Self {
+            name: name.to_owned(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            hosts: hosts.iter().map(|host| host.to_string()).collect(),
+            frontends,
+        }
+    }
+
+    /// Returns the target frontend of the service (the first one) if any
+    pub fn target(&self) -> Option<&Url> {
+        self.frontends.first()
+    }
+
+    /// Returns [`true`] if the `host` is one of the service [`hosts`] or [`frontends`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::Service;
+    /// use url::Url;
+    ///
+    /// let service: Service = Service::new(
+    ///     "reddit",
+    ///     &[],
+    ///     &["reddit.com"],
+    ///     vec![Url::parse("https://libredd.it").unwrap()],
+    /// );
+    ///
+    /// assert!(service.contain("reddit.com"));
+    /// assert!(service.contain("libredd.it"));
+    /// assert!(!service.contain("twitter.com"));
+    /// ```
+    ///
+    /// [`hosts`]: Service#structfield.hosts
+    /// [`frontends`]: Service#structfield.frontends
+    pub fn contain(&self, host: &str) -> bool {
+        self.hosts
+            .iter()
+            .any(|service_host| same_host(service_host, host))
+            || self
+                .frontends
+                .iter()
+                .any(|frontend| frontend.host_str().is_some_and(|new| same_host(new, host)))
+    }
+
+    /// Compile the service to [`Domain`]s, every host is changed to the [`target`]
+    ///
+    /// The alternative frontends are not compiled, they are kept in the [`Group`]
+    /// of the service, see [`Config::add_service`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Domain, Service};
+    /// use url::Url;
+    ///
+    /// let service: Service = Service::new(
+    ///     "youtube",
+    ///     &["video"],
+    ///     &["youtube.com", "youtu.be"],
+    ///     vec![
+    ///         Url::parse("https://piped.kavin.rocks").unwrap(),
+    ///         Url::parse("https://yewtu.be").unwrap(),
+    ///     ],
+    /// );
+    ///
+    /// let domains: Vec<Domain> = service.domains().unwrap();
+    /// assert_eq!(domains.len(), 2);
+    /// assert_eq!(
+    ///     domains[1],
+    ///     Domain::try_from(("https://youtu.be/", "https://piped.kavin.rocks/")).unwrap()
+    /// );
+    /// assert!(Service::new("bad", &[], &["not a host"], Vec::new()).domains().is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// - Returns [`InvalidOldDomain`] error if a host of the service is invalid
+    /// - Returns [`InvalidNewDomain`] error if the target has no host or it is one of the hosts,
+    ///   see [`Domain::validate`]
+    ///
+    /// [`InvalidOldDomain`]: crate::types::errors::DomainChangerError::InvalidOldDomain
+    /// [`InvalidNewDomain`]: crate::types::errors::DomainChangerError::InvalidNewDomain
+    /// [`target`]: method@Service::target
+    /// [`Config::add_service`]: crate::types::Config::add_service
+    pub fn domains(&self) -> DomainChangerResult<Vec<Domain>> {
+        let hosts: Vec<Url> = self.host_urls()?;
+        let Some(target) = self.target() else {
+            return Ok(Vec::new());
+        };
+        hosts
+            .into_iter()
+            .map(|host| {
+                let domain: Domain = Domain::new(host, target.clone());
+                domain.validate()?;
+                Ok(domain)
+            })
+            .collect()
+    }
+
+    /// Returns the [`Group`] of the service, the hosts of the group are
+    /// the parsed hosts of the service and it keeps the frontends
+    pub(crate) fn group(&self) -> DomainChangerResult<Group> {
+        let mut hosts: Vec<String> = Vec::new();
+        for url in self.host_urls()? {
+            if let Some(host) = url.host_str() {
+                if !hosts.iter().any(|group_host| group_host == host) {
+                    hosts.push(host.to_owned());
+                }
+            }
+        }
+        Ok(Group {
+            name: self.name.clone(),
+            tags: self.tags.clone(),
+            hosts,
+            enabled: true,
+            frontends: self.frontends.clone(),
+        })
+    }
+
+    /// Parse the hosts of the service to [`Url`]s
+    fn host_urls(&self) -> DomainChangerResult<Vec<Url>> {
+        self.hosts
+            .iter()
+            .map(|host| {
+                Url::parse(&format!("https://{}/", host))
+                    .ok()
+                    .filter(|url| url.host_str().is_some() && url.path() == "/")
+                    .ok_or_else(|| {
+                        DomainChangerError::InvalidOldDomain(format!(
+                            "'{}', is invalid old domain",
+                            host
+                        ))
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Implementation [`ToFromJson`] to [`Service`]
+///
+/// # Example
+/// ```rust
+/// use domain_changer::types::{Service, ToFromJson};
+/// use url::Url;
+///
+/// let service: Service = Service::new(
+///     "twitter",
+///     &[],
+///     &["twitter.com", "t.co"],
+///     vec![Url::parse("https://nitter.net").unwrap()],
+/// );
+/// assert_eq!(
+///     service.to_json().unwrap(),
+///     "{\"name\":\"twitter\",\"hosts\":[\"twitter.com\",\"t.co\"],\"frontends\":[\"https://nitter.net/\"]}"
+/// );
+/// assert_eq!(Service::from_json(&service.to_json().unwrap()).unwrap(), service);
+/// ```
+#[cfg(feature = "json")]
+impl ToFromJson<'_> for Service {}
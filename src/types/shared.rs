@@ -0,0 +1,386 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Thread-safe handle of [`Config`] that can be reloaded while it is used
+
+use super::errors::DomainChangerResult;
+#[cfg(feature = "json")]
+use super::{errors::DomainChangerError, ToFromJson};
+use crate::types::{Config, Domain, SafetyPolicy};
+use arc_swap::ArcSwap;
+#[cfg(feature = "watch")]
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+#[cfg(feature = "json")]
+use std::path::Path;
+use std::sync::Arc;
+
+/// Thread-safe handle of [`Config`], the config can be replaced atomically while it is used.
+/// The readers never block, and they see the old config or the new one, never a half-applied one.
+/// The clones of the handle share the same config
+///
+/// # Example
+/// ```rust
+/// use domain_changer::parse_string;
+/// use domain_changer::types::{Config, SafetyPolicy, SharedConfig};
+///
+/// let shared: SharedConfig = SharedConfig::with_policy(Config::default(), SafetyPolicy::default()).unwrap();
+/// let reader: SharedConfig = shared.clone();
+/// assert_eq!(
+///     parse_string(&reader.load(), "twitter.com/Awiteb".to_owned()),
+///     "https://nitter.net/Awiteb"
+/// );
+///
+/// shared
+///     .reload_json(r#"{"twitter.com":"nitter.it"}"#)
+///     .unwrap();
+/// assert_eq!(
+///     parse_string(&reader.load(), "twitter.com/Awiteb".to_owned()),
+///     "https://nitter.it/Awiteb"
+/// );
+/// // The invalid config is not applied
+/// assert!(shared
+///     .reload_json(r#"{"twitter.com":"http://localhost"}"#)
+///     .unwrap_err()
+///     .is_unsafe_target());
+/// assert_eq!(reader.load().old_hosts(), vec!["twitter.com"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedConfig {
+    config: Arc<ArcSwap<Config>>,
+    policy: Option<Arc<SafetyPolicy>>,
+}
+
+impl SharedConfig {
+    /// Create new [`SharedConfig`] without [`SafetyPolicy`], the domains of the config
+    /// and the replacements of it are validated, see [`Domain::validate`]
+    ///
+    /// # Errors
+    /// Returns [`InvalidOldDomain`] or [`InvalidNewDomain`] error if a domain is invalid
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, Domain, SharedConfig};
+    ///
+    /// let mut config: Config = Config::default();
+    /// // Bypass the validation of `Config::insert`
+    /// config.domains.push(Domain::new(
+    ///     "https://t.co".parse().unwrap(),
+    ///     "https://t.co".parse().unwrap(),
+    /// ));
+    /// assert!(SharedConfig::new(config).unwrap_err().is_invalid_new_domain());
+    /// ```
+    ///
+    /// [`Domain::validate`]: crate::types::Domain::validate
+    /// [`InvalidOldDomain`]: crate::types::errors::DomainChangerError::InvalidOldDomain
+    /// [`InvalidNewDomain`]: crate::types::errors::DomainChangerError::InvalidNewDomain
+    pub fn new(config: Config) -> DomainChangerResult<Self> {
+        validate(&config, None)?;
+        Ok(Self {
+            config: Arc::new(ArcSwap::from_pointee(config)),
+            policy: None,
+        })
+    }
+
+    /// Same as [`SharedConfig::new`] but the config and the replacements of it are validated
+    /// with the `policy` too, see [`Config::validate`]
+    ///
+    /// # Errors
+    /// Returns the errors of [`SharedConfig::new`], and [`UnsafeTarget`] error if the config
+    /// is not allowed by the `policy`
+    ///
+    /// [`UnsafeTarget`]: crate::types::errors::DomainChangerError::UnsafeTarget
+    pub fn with_policy(config: Config, policy: SafetyPolicy) -> DomainChangerResult<Self> {
+        validate(&config, Some(&policy))?;
+        Ok(Self {
+            config: Arc::new(ArcSwap::from_pointee(config)),
+            policy: Some(Arc::new(policy)),
+        })
+    }
+
+    /// Returns the current config, it is not changed by the later replacements
+    pub fn load(&self) -> Arc<Config> {
+        self.config.load_full()
+    }
+
+    /// Replace the config atomically, returns the previous config
+    ///
+    /// # Errors
+    /// Returns the errors of [`SharedConfig::with_policy`], the current config is kept
+    pub fn replace(&self, config: Config) -> DomainChangerResult<Arc<Config>> {
+        validate(&config, self.policy.as_deref())?;
+        Ok(self.config.swap(Arc::new(config)))
+    }
+
+    /// Deserialize [`Config`] from json and replace the current config with it,
+    /// returns the previous config
+    ///
+    /// # Errors
+    /// - Returns [`InvalidConfig`] error if the json is invalid
+    /// - Returns the errors of [`SharedConfig::replace`]
+    ///
+    /// The current config is kept if there is an error
+    ///
+    /// [`InvalidConfig`]: crate::types::errors::DomainChangerError::InvalidConfig
+    #[cfg(feature = "json")]
+    pub fn reload_json(&self, json: &str) -> DomainChangerResult<Arc<Config>> {
+        self.replace(
+            Config::from_json(json)
+                .map_err(|err| DomainChangerError::InvalidConfig(err.to_string()))?,
+        )
+    }
+
+    /// Same as [`SharedConfig::reload_json`] but reads the json from the file
+    ///
+    /// # Errors
+    /// Returns [`ReloadFailed`] error if the file can't be read, and the errors of
+    /// [`SharedConfig::reload_json`]
+    ///
+    /// [`ReloadFailed`]: crate::types::errors::DomainChangerError::ReloadFailed
+    #[cfg(feature = "json")]
+    pub fn reload_file(&self, path: impl AsRef<Path>) -> DomainChangerResult<Arc<Config>> {
+        let path: &Path = path.as_ref();
+        let json: String = std::fs::read_to_string(path).map_err(|err| {
+            DomainChangerError::ReloadFailed(format!("'{}', {}", path.display(), err))
+        })?;
+        self.reload_json(&json)
+    }
+
+    /// Watch the json file of the config and reload it when it is changed, `on_reload` is
+    /// called with the result of every reload. The failed reload keeps the current config
+    ///
+    /// The watching stops when the returned [`ConfigWatcher`] is dropped
+    ///
+    /// # Errors
+    /// Returns [`ReloadFailed`] error if the file can't be watched
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use domain_changer::types::{Config, ConfigWatcher, SharedConfig};
+    ///
+    /// let shared: SharedConfig = SharedConfig::new(Config::default()).unwrap();
+    /// let _watcher: ConfigWatcher = shared
+    ///     .watch("config.json", |result| {
+    ///         if let Err(err) = result {
+    ///             eprintln!("Failed to reload the config: {}", err);
+    ///         }
+    ///     })
+    ///     .unwrap();
+    /// ```
+    ///
+    /// [`ReloadFailed`]: crate::types::errors::DomainChangerError::ReloadFailed
+    #[cfg(feature = "watch")]
+    pub fn watch<F>(
+        &self,
+        path: impl AsRef<Path>,
+        mut on_reload: F,
+    ) -> DomainChangerResult<ConfigWatcher>
+    where
+        F: FnMut(DomainChangerResult<()>) + Send + 'static,
+    {
+        let path: std::path::PathBuf = std::path::absolute(path.as_ref()).map_err(|err| {
+            DomainChangerError::ReloadFailed(format!("'{}', {}", path.as_ref().display(), err))
+        })?;
+        let watch_failed = |err: notify::Error| {
+            DomainChangerError::ReloadFailed(format!("'{}', {}", path.display(), err))
+        };
+        let shared: SharedConfig = self.clone();
+        let file: std::path::PathBuf = path.clone();
+        let mut watcher: RecommendedWatcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+                Ok(event)
+                    if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                        && event.paths.contains(&file) =>
+                {
+                    on_reload(shared.reload_file(&file).map(|_| ()))
+                }
+                Ok(_) => {}
+                Err(err) => on_reload(Err(DomainChangerError::ReloadFailed(format!(
+                    "'{}', {}",
+                    file.display(),
+                    err
+                )))),
+            })
+            .map_err(watch_failed)?;
+        // The parent directory is watched, the editors replace the file instead of writing to it
+        watcher
+            .watch(
+                path.parent().unwrap_or(Path::new("/")),
+                RecursiveMode::NonRecursive,
+            )
+            .map_err(watch_failed)?;
+        Ok(ConfigWatcher { _watcher: watcher })
+    }
+}
+
+/// Validate the domains of the `config`, then validate it with the `policy` if any
+fn validate(config: &Config, policy: Option<&SafetyPolicy>) -> DomainChangerResult<()> {
+    config.domains.iter().try_for_each(Domain::validate)?;
+    policy.map_or(Ok(()), |policy| config.validate(policy))
+}
+
+/// Watcher of the config file, see [`SharedConfig::watch`]. The watching stops when it is dropped
+#[cfg(feature = "watch")]
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SharedConfig;
+    use crate::types::{Config, Domain};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    /// Config of `count` domains with the same new host
+    fn config(version: usize, count: usize) -> Config {
+        Config::new(
+            (0..count)
+                .map(|index| {
+                    Domain::lenient(
+                        &format!("old{}.com", index),
+                        &format!("v{}.example.com", version),
+                    )
+                    .unwrap()
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn shared_config_test() {
+        let shared: SharedConfig = SharedConfig::new(config(0, 16)).unwrap();
+        let stop: Arc<AtomicBool> = Arc::default();
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let (shared, stop) = (shared.clone(), Arc::clone(&stop));
+                thread::spawn(move || {
+                    while !stop.load(Ordering::Relaxed) {
+                        let config = shared.load();
+                        // The readers never see a half-applied config
+                        assert_eq!(config.domains.len(), 16);
+                        assert_eq!(
+                            config
+                                .new_hosts()
+                                .iter()
+                                .filter(|host| **host == config.new_hosts()[0])
+                                .count(),
+                            16
+                        );
+                    }
+                })
+            })
+            .collect();
+        for version in 1..=100 {
+            let previous = shared.replace(config(version, 16)).unwrap();
+            assert_eq!(
+                previous.new_hosts()[0],
+                format!("v{}.example.com", version - 1)
+            );
+        }
+        stop.store(true, Ordering::Relaxed);
+        readers
+            .into_iter()
+            .for_each(|reader| reader.join().unwrap());
+        assert_eq!(shared.load().new_hosts()[0], "v100.example.com");
+    }
+
+    #[test]
+    fn validate_test() {
+        let shared: SharedConfig = SharedConfig::new(config(0, 2)).unwrap();
+        let mut invalid: Config = config(1, 2);
+        invalid.domains[1].new = invalid.domains[1].old.clone();
+        // The config is validated without a policy, and the current config is kept
+        assert!(shared
+            .replace(invalid.clone())
+            .unwrap_err()
+            .is_invalid_new_domain());
+        assert!(SharedConfig::new(invalid).is_err());
+        assert_eq!(shared.load().new_hosts()[0], "v0.example.com");
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn reload_failure_test() {
+        let shared: SharedConfig = SharedConfig::new(Config::default()).unwrap();
+        assert!(shared.reload_json("{").unwrap_err().is_invalid_config());
+        assert!(shared
+            .reload_file("/nonexistent/config.json")
+            .unwrap_err()
+            .is_reload_failed());
+        assert_eq!(*shared.load(), Config::default());
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn watch_test() {
+        use crate::types::errors::DomainChangerResult;
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        /// Temporary directory, it is removed when it is dropped
+        struct TempDir(std::path::PathBuf);
+
+        impl Drop for TempDir {
+            fn drop(&mut self) {
+                let _ = std::fs::remove_dir_all(&self.0);
+            }
+        }
+
+        let temp: TempDir = TempDir(
+            std::env::temp_dir().join(format!("domain_changer-watch-{}", std::process::id())),
+        );
+        let dir = &temp.0;
+        let _ = std::fs::remove_dir_all(dir);
+        std::fs::create_dir_all(dir).unwrap();
+        let path = dir.join("config.json");
+        std::fs::write(&path, r#"{"twitter.com":"nitter.net"}"#).unwrap();
+
+        let shared: SharedConfig = SharedConfig::new(Config::default()).unwrap();
+        let (sender, receiver) = mpsc::channel::<DomainChangerResult<()>>();
+        let _watcher = shared
+            .watch(&path, move |result| {
+                let _ = sender.send(result);
+            })
+            .unwrap();
+        let wait_for = |new_host: &str| {
+            while let Ok(result) = receiver.recv_timeout(Duration::from_secs(10)) {
+                if result.is_ok() && shared.load().new_hosts() == vec![new_host] {
+                    return true;
+                }
+            }
+            false
+        };
+
+        std::fs::write(&path, r#"{"twitter.com":"nitter.it"}"#).unwrap();
+        assert!(wait_for("nitter.it"));
+        // The invalid config is reported and the current config is kept
+        std::fs::write(&path, "{").unwrap();
+        assert!(
+            std::iter::from_fn(|| receiver.recv_timeout(Duration::from_secs(10)).ok())
+                .any(|result| result.is_err_and(|err| err.is_invalid_config()))
+        );
+        assert_eq!(shared.load().new_hosts(), vec!["nitter.it"]);
+        // Replacing the file like the editors
+        std::fs::write(
+            dir.join("config.json.tmp"),
+            r#"{"twitter.com":"nitter.cz"}"#,
+        )
+        .unwrap();
+        std::fs::rename(dir.join("config.json.tmp"), &path).unwrap();
+        assert!(wait_for("nitter.cz"));
+    }
+}
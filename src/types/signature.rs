@@ -0,0 +1,492 @@
+//     Rust library that helps you change the domain of the link to another domain
+//      Copyright (C) 2022  TheAwiteb <awiteb@hotmail.com>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the Free
+// Software Foundation, version 3 of the License
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public License for more
+// details.
+//
+// You should have received a copy of the GNU Affero General Public License along
+// with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Verification of the detached signatures of the serialized configs
+//!
+//! The signature can be:
+//! - raw Ed25519 signature (64 bytes, base64 encoded) of the serialized config
+//! - [minisign](https://jedisct1.github.io/minisign/) signature, the trusted comment of it
+//!   can contain `expires:<unix timestamp>` to expire the signature
+
+use super::errors::{DomainChangerError, DomainChangerResult};
+#[cfg(feature = "std")]
+use crate::types::{Config, ToFromJson};
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use blake2::{Blake2b512, Digest};
+use core::str::FromStr;
+use ed25519_dalek::{Signature as Ed25519Signature, VerifyingKey};
+
+/// The algorithm of the minisign signature of the data itself
+const ED25519: &[u8; 2] = b"Ed";
+/// The algorithm of the minisign signature of the BLAKE2b-512 hash of the data
+const ED25519_PREHASHED: &[u8; 2] = b"ED";
+const UNTRUSTED_COMMENT: &str = "untrusted comment:";
+const TRUSTED_COMMENT: &str = "trusted comment: ";
+
+/// Pinned Ed25519 public key to verify the signatures with it
+///
+/// The key can be parsed from the base64 of the raw key (32 bytes) or from
+/// minisign public key (the `.pub` file or the base64 line of it)
+///
+/// # Example
+/// ```rust
+/// use domain_changer::types::PublicKey;
+///
+/// let minisign: PublicKey = "untrusted comment: minisign public key 66DC7A8C5E8C3D3B\n\
+///     RWQ7PYxejHrcZupKbGPinFIKvvVQexMuxfmVR3auvr57kkIe6mkURtIs"
+///     .parse()
+///     .unwrap();
+/// let raw: PublicKey = "6kpsY+KcUgq+9VB7Ey7F+ZVHdq6+vnuSQh7qaRRG0iw=".parse().unwrap();
+/// assert_eq!(minisign.as_bytes(), raw.as_bytes());
+/// assert!("twitter.com".parse::<PublicKey>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    /// The minisign key id, the minisign signatures of other keys are not verified with it
+    id: Option<[u8; 8]>,
+    key: VerifyingKey,
+}
+
+impl PublicKey {
+    /// Create [`PublicKey`] from the raw Ed25519 public key
+    ///
+    /// # Errors
+    /// Returns [`BadSignature`] error if the key is not valid Ed25519 public key
+    ///
+    /// [`BadSignature`]: crate::types::errors::DomainChangerError::BadSignature
+    pub fn from_bytes(bytes: &[u8; 32]) -> DomainChangerResult<Self> {
+        VerifyingKey::from_bytes(bytes)
+            .map(|key| Self { id: None, key })
+            .map_err(|_| DomainChangerError::BadSignature("Invalid Ed25519 public key".to_owned()))
+    }
+
+    /// Returns the raw Ed25519 public key
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        self.key.as_bytes()
+    }
+
+    /// Returns [`true`] if the key can verify the signature of the minisign key `id`
+    fn accepts(&self, id: &[u8; 8]) -> bool {
+        self.id.as_ref().is_none_or(|key_id| key_id == id)
+    }
+
+    fn verify(&self, message: &[u8], signature: &Ed25519Signature) -> bool {
+        self.key.verify_strict(message, signature).is_ok()
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = DomainChangerError;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        let bytes: Vec<u8> = decode(last_line(key), "public key")?;
+        match bytes.len() {
+            32 => Self::from_bytes(bytes.as_slice().try_into().expect("32 bytes")),
+            42 if bytes.starts_with(ED25519) => {
+                Self::from_bytes(bytes[10..].try_into().expect("32 bytes")).map(|key| Self {
+                    id: Some(bytes[2..10].try_into().expect("8 bytes")),
+                    ..key
+                })
+            }
+            _ => Err(DomainChangerError::BadSignature(format!(
+                "'{}' is not Ed25519 or minisign public key",
+                key
+            ))),
+        }
+    }
+}
+
+/// Detached signature of serialized config, raw Ed25519 signature or minisign signature
+///
+/// Parsing empty signature returns [`Unsigned`] error
+///
+/// [`Unsigned`]: crate::types::errors::DomainChangerError::Unsigned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature(Repr);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Repr {
+    Ed25519(Ed25519Signature),
+    Minisign {
+        id: [u8; 8],
+        prehashed: bool,
+        signature: Ed25519Signature,
+        trusted_comment: String,
+        global_signature: Ed25519Signature,
+        expires: Option<u64>,
+    },
+}
+
+impl Signature {
+    /// Returns the expiry (unix timestamp) of the signature, it is the `expires:<unix timestamp>`
+    /// of the minisign trusted comment
+    pub fn expires(&self) -> Option<u64> {
+        match &self.0 {
+            Repr::Ed25519(_) => None,
+            Repr::Minisign { expires, .. } => *expires,
+        }
+    }
+
+    /// Verify the signature of the `data` with one of the pinned `keys`, `now` is the current
+    /// unix timestamp to check the expiry of the signature
+    ///
+    /// # Errors
+    /// - Returns [`BadSignature`] error if the signature is not made by one of the `keys`
+    /// - Returns [`ExpiredSignature`] error if the signature is expired
+    ///
+    /// [`BadSignature`]: crate::types::errors::DomainChangerError::BadSignature
+    /// [`ExpiredSignature`]: crate::types::errors::DomainChangerError::ExpiredSignature
+    pub fn verify(&self, data: &[u8], keys: &[PublicKey], now: u64) -> DomainChangerResult<()> {
+        let verified: bool = match &self.0 {
+            Repr::Ed25519(signature) => keys.iter().any(|key| key.verify(data, signature)),
+            Repr::Minisign {
+                id,
+                prehashed,
+                signature,
+                trusted_comment,
+                global_signature,
+                ..
+            } => {
+                let message: Vec<u8> = if *prehashed {
+                    Blake2b512::digest(data).to_vec()
+                } else {
+                    data.to_vec()
+                };
+                let global_message: Vec<u8> =
+                    [&signature.to_bytes()[..], trusted_comment.as_bytes()].concat();
+                keys.iter().filter(|key| key.accepts(id)).any(|key| {
+                    key.verify(&message, signature) && key.verify(&global_message, global_signature)
+                })
+            }
+        };
+        if !verified {
+            return Err(DomainChangerError::BadSignature(
+                "The signature is not made by a pinned key".to_owned(),
+            ));
+        }
+        match self.expires() {
+            Some(expires) if expires <= now => Err(DomainChangerError::ExpiredSignature(format!(
+                "The signature is expired at '{}'",
+                expires
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl FromStr for Signature {
+    type Err = DomainChangerError;
+
+    fn from_str(signature: &str) -> Result<Self, Self::Err> {
+        let signature: &str = signature.trim();
+        if signature.is_empty() {
+            return Err(DomainChangerError::Unsigned(
+                "The signature is empty".to_owned(),
+            ));
+        }
+        if !signature.starts_with(UNTRUSTED_COMMENT) {
+            return to_signature(&decode(signature, "signature")?)
+                .map(|sig| Self(Repr::Ed25519(sig)));
+        }
+
+        let lines: Vec<&str> = signature.lines().map(str::trim).collect();
+        let [_, signature, trusted_comment, global_signature] = lines[..] else {
+            return Err(DomainChangerError::BadSignature(
+                "The minisign signature must be 4 lines".to_owned(),
+            ));
+        };
+        let signature: Vec<u8> = decode(signature, "signature")?;
+        let trusted_comment: &str =
+            trusted_comment
+                .strip_prefix(TRUSTED_COMMENT)
+                .ok_or_else(|| {
+                    DomainChangerError::BadSignature(
+                        "The minisign signature has no trusted comment".to_owned(),
+                    )
+                })?;
+        if signature.len() != 74 {
+            return Err(DomainChangerError::BadSignature(
+                "Invalid minisign signature".to_owned(),
+            ));
+        }
+        let prehashed: bool = match &signature[..2] {
+            algorithm if algorithm == ED25519 => false,
+            algorithm if algorithm == ED25519_PREHASHED => true,
+            _ => {
+                return Err(DomainChangerError::BadSignature(
+                    "Unsupported minisign signature algorithm".to_owned(),
+                ))
+            }
+        };
+        Ok(Self(Repr::Minisign {
+            id: signature[2..10].try_into().expect("8 bytes"),
+            prehashed,
+            signature: to_signature(&signature[10..])?,
+            trusted_comment: trusted_comment.to_owned(),
+            global_signature: to_signature(&decode(global_signature, "signature")?)?,
+            expires: expires(trusted_comment)?,
+        }))
+    }
+}
+
+#[cfg(feature = "std")]
+impl Config {
+    /// Verify the detached `signature` of the `json` with one of the pinned `keys`, then
+    /// deserialize [`Config`] from it. The `signature` is the content of the signature file,
+    /// raw Ed25519 signature (base64) or minisign signature, see [`Signature`]
+    ///
+    /// # Errors
+    /// - Returns [`Unsigned`] error if there is no signature
+    /// - Returns [`BadSignature`] error if the signature is not made by one of the `keys`
+    /// - Returns [`ExpiredSignature`] error if the signature is expired
+    /// - Returns [`InvalidConfig`] error if the json is invalid
+    ///
+    /// # Example
+    /// ```rust
+    /// use domain_changer::types::{Config, PublicKey};
+    ///
+    /// let keys: Vec<PublicKey> = vec!["6kpsY+KcUgq+9VB7Ey7F+ZVHdq6+vnuSQh7qaRRG0iw=".parse().unwrap()];
+    /// let json: &str = r#"{"twitter.com":"nitter.net"}"#;
+    /// let signature: &str = "x1/vQlDMwi0Exe9qD8v6FmglJlOGcXicMxYW/LUbsvuGJijQbOhUTyvb4crpjISq83k4qHLBaudYuHi0DaGWAA==";
+    ///
+    /// assert_eq!(
+    ///     Config::from_signed_json(json, Some(signature), &keys)
+    ///         .unwrap()
+    ///         .old_hosts(),
+    ///     vec!["twitter.com"]
+    /// );
+    /// assert!(Config::from_signed_json(json, None, &keys)
+    ///     .unwrap_err()
+    ///     .is_unsigned());
+    /// assert!(Config::from_signed_json(r#"{"twitter.com":"evil.com"}"#, Some(signature), &keys)
+    ///     .unwrap_err()
+    ///     .is_bad_signature());
+    /// ```
+    ///
+    /// [`Unsigned`]: crate::types::errors::DomainChangerError::Unsigned
+    /// [`BadSignature`]: crate::types::errors::DomainChangerError::BadSignature
+    /// [`ExpiredSignature`]: crate::types::errors::DomainChangerError::ExpiredSignature
+    /// [`InvalidConfig`]: crate::types::errors::DomainChangerError::InvalidConfig
+    pub fn from_signed_json(
+        json: &str,
+        signature: Option<&str>,
+        keys: &[PublicKey],
+    ) -> DomainChangerResult<Self> {
+        let signature: Signature = signature
+            .ok_or_else(|| DomainChangerError::Unsigned("The config is not signed".to_owned()))?
+            .parse()?;
+        let now: u64 = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs());
+        signature.verify(json.as_bytes(), keys, now)?;
+        Config::from_json(json).map_err(|err| DomainChangerError::InvalidConfig(err.to_string()))
+    }
+}
+
+/// Returns the last non-empty line of the `text`, the first line of minisign key is comment
+fn last_line(text: &str) -> &str {
+    text.lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .unwrap_or_default()
+}
+
+fn decode(base64: &str, what: &str) -> DomainChangerResult<Vec<u8>> {
+    STANDARD.decode(base64).map_err(|err| {
+        DomainChangerError::BadSignature(format!("Invalid base64 {}: {}", what, err))
+    })
+}
+
+fn to_signature(bytes: &[u8]) -> DomainChangerResult<Ed25519Signature> {
+    Ed25519Signature::from_slice(bytes)
+        .map_err(|_| DomainChangerError::BadSignature("Invalid Ed25519 signature".to_owned()))
+}
+
+/// Returns the `expires:<unix timestamp>` of the trusted comment
+fn expires(trusted_comment: &str) -> DomainChangerResult<Option<u64>> {
+    trusted_comment
+        .split_whitespace()
+        .find_map(|field| field.strip_prefix("expires:"))
+        .map(|expires| {
+            expires.parse().map_err(|_| {
+                DomainChangerError::BadSignature(format!("Invalid expiry '{}'", expires))
+            })
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PublicKey, Signature};
+    use alloc::{format, string::String, vec::Vec};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use blake2::{Blake2b512, Digest};
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const DATA: &[u8] = br#"{"twitter.com":"nitter.net"}"#;
+    const ID: [u8; 8] = *b"\x3b\x3d\x8c\x5e\x8c\x7a\xdc\x66";
+
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    fn public_key(seed: u8) -> PublicKey {
+        PublicKey::from_bytes(signing_key(seed).verifying_key().as_bytes()).unwrap()
+    }
+
+    fn minisign_public_key(seed: u8, id: [u8; 8]) -> PublicKey {
+        let key: Vec<u8> = [
+            &b"Ed"[..],
+            &id,
+            signing_key(seed).verifying_key().as_bytes(),
+        ]
+        .concat();
+        format!(
+            "untrusted comment: minisign public key\n{}\n",
+            STANDARD.encode(key)
+        )
+        .parse()
+        .unwrap()
+    }
+
+    /// Sign the `data` like `minisign -S`
+    fn minisign(seed: u8, data: &[u8], prehashed: bool, trusted_comment: &str) -> String {
+        let key: SigningKey = signing_key(seed);
+        let (algorithm, message): (&[u8], Vec<u8>) = if prehashed {
+            (b"ED", Blake2b512::digest(data).to_vec())
+        } else {
+            (b"Ed", data.to_vec())
+        };
+        let signature: [u8; 64] = key.sign(&message).to_bytes();
+        let global_signature: [u8; 64] = key
+            .sign(&[&signature[..], trusted_comment.as_bytes()].concat())
+            .to_bytes();
+        format!(
+            "untrusted comment: signature from minisign secret key\n{}\ntrusted comment: {}\n{}\n",
+            STANDARD.encode([algorithm, &ID, &signature].concat()),
+            trusted_comment,
+            STANDARD.encode(global_signature)
+        )
+    }
+
+    #[test]
+    fn ed25519_test() {
+        let signature: Signature = STANDARD
+            .encode(signing_key(1).sign(DATA).to_bytes())
+            .parse()
+            .unwrap();
+        assert!(signature.verify(DATA, &[public_key(1)], 0).is_ok());
+        assert!(signature
+            .verify(DATA, &[public_key(2), public_key(1)], 0)
+            .is_ok());
+        assert!(signature
+            .verify(br#"{"twitter.com":"evil.com"}"#, &[public_key(1)], 0)
+            .unwrap_err()
+            .is_bad_signature());
+        assert!(signature
+            .verify(DATA, &[public_key(2)], 0)
+            .unwrap_err()
+            .is_bad_signature());
+        assert!(signature
+            .verify(DATA, &[], 0)
+            .unwrap_err()
+            .is_bad_signature());
+        assert_eq!(signature.expires(), None);
+    }
+
+    #[test]
+    fn minisign_test() {
+        for prehashed in [false, true] {
+            let signature: Signature = minisign(1, DATA, prehashed, "timestamp:1700000000")
+                .parse()
+                .unwrap();
+            assert!(signature
+                .verify(DATA, &[minisign_public_key(1, ID)], 0)
+                .is_ok());
+            // The raw key accepts all key ids
+            assert!(signature.verify(DATA, &[public_key(1)], 0).is_ok());
+            assert!(signature
+                .verify(DATA, &[minisign_public_key(1, [0; 8])], 0)
+                .unwrap_err()
+                .is_bad_signature());
+            assert!(signature
+                .verify(b"{}", &[minisign_public_key(1, ID)], 0)
+                .unwrap_err()
+                .is_bad_signature());
+        }
+
+        // Tampered trusted comment
+        let signature: String = minisign(1, DATA, true, "timestamp:1700000000 expires:1700000100")
+            .replace("expires:1700000100", "expires:1900000000");
+        assert!(signature
+            .parse::<Signature>()
+            .unwrap()
+            .verify(DATA, &[public_key(1)], 0)
+            .unwrap_err()
+            .is_bad_signature());
+    }
+
+    #[test]
+    fn expiry_test() {
+        let signature: Signature =
+            minisign(1, DATA, true, "timestamp:1700000000 expires:1700000100")
+                .parse()
+                .unwrap();
+        assert_eq!(signature.expires(), Some(1700000100));
+        assert!(signature.verify(DATA, &[public_key(1)], 1700000099).is_ok());
+        assert!(signature
+            .verify(DATA, &[public_key(1)], 1700000100)
+            .unwrap_err()
+            .is_expired_signature());
+        // The expiry of bad signature is not trusted
+        assert!(signature
+            .verify(DATA, &[public_key(2)], 1700000100)
+            .unwrap_err()
+            .is_bad_signature());
+        assert!(minisign(1, DATA, true, "expires:soon")
+            .parse::<Signature>()
+            .unwrap_err()
+            .is_bad_signature());
+    }
+
+    #[test]
+    fn parse_test() {
+        assert!("".parse::<Signature>().unwrap_err().is_unsigned());
+        assert!(" \n".parse::<Signature>().unwrap_err().is_unsigned());
+        assert!("not base64"
+            .parse::<Signature>()
+            .unwrap_err()
+            .is_bad_signature());
+        assert!(STANDARD
+            .encode([0; 32])
+            .parse::<Signature>()
+            .unwrap_err()
+            .is_bad_signature());
+        assert!("untrusted comment: x\nRWQ="
+            .parse::<Signature>()
+            .unwrap_err()
+            .is_bad_signature());
+        assert!(STANDARD
+            .encode([0; 16])
+            .parse::<PublicKey>()
+            .unwrap_err()
+            .is_bad_signature());
+        assert_eq!(
+            minisign_public_key(1, ID).as_bytes(),
+            public_key(1).as_bytes()
+        );
+    }
+}
@@ -1,6 +1,7 @@
 #[cfg(feature = "json")]
-use serde::{Deserialize, Serialize};
+use alloc::string::String;
 #[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
 use serde_json::{from_str, to_string, Result as SerdeResult};
 
 /// Trait that [`serialize`] and [`deserialize`] from/to json